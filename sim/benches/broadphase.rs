@@ -0,0 +1,107 @@
+//! Brute force SAT against every wall vs. querying the quadtree first.
+//! Run with `cargo bench -p taxi-sim`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use taxi_sim::math::{Point, Rect, polygon_bounds, polygon_collision};
+use taxi_sim::quadtree::Quadtree;
+use taxi_sim::wall::Wall;
+
+const WALL_SPACING: f32 = 250.0;
+const QUERIES: usize = 10_000;
+
+fn main() {
+    for walls_per_side in [10, 32, 100] {
+        let walls = build_wall_grid(walls_per_side);
+        let probes = build_probes(walls_per_side);
+
+        let half_size = walls_per_side as f32 * WALL_SPACING;
+        let mut tree = Quadtree::new(Rect::new(
+            -WALL_SPACING,
+            -WALL_SPACING,
+            half_size + WALL_SPACING * 2.0,
+            half_size + WALL_SPACING * 2.0,
+        ));
+        for (index, wall) in walls.iter().enumerate() {
+            tree.insert(index, wall.bounds());
+        }
+
+        let (brute_force_time, brute_force_hits) = time_it(|| {
+            let mut hits = 0;
+            for probe in probes.iter() {
+                for wall in walls.iter() {
+                    if polygon_collision(probe, &wall.points).is_some() {
+                        hits += 1;
+                    }
+                }
+            }
+            hits
+        });
+
+        let (quadtree_time, quadtree_hits) = time_it(|| {
+            let mut hits = 0;
+            for probe in probes.iter() {
+                for index in tree.query(&polygon_bounds(probe)) {
+                    if polygon_collision(probe, &walls[index].points).is_some() {
+                        hits += 1;
+                    }
+                }
+            }
+            hits
+        });
+
+        assert_eq!(brute_force_hits, quadtree_hits);
+
+        println!(
+            "{:>6} walls: brute force {:>10.3?}  quadtree {:>10.3?}  ({:.1}x, {} hits)",
+            walls.len(),
+            brute_force_time,
+            quadtree_time,
+            brute_force_time.as_secs_f64() / quadtree_time.as_secs_f64(),
+            quadtree_hits,
+        );
+    }
+}
+
+fn time_it(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (start.elapsed(), result)
+}
+
+fn build_wall_grid(walls_per_side: usize) -> Vec<Wall> {
+    let mut walls = Vec::with_capacity(walls_per_side * walls_per_side);
+    for row in 0..walls_per_side {
+        for col in 0..walls_per_side {
+            walls.push(Wall::new(
+                col as f32 * WALL_SPACING,
+                row as f32 * WALL_SPACING,
+            ));
+        }
+    }
+    walls
+}
+
+/// Taxi sized squares scattered over the grid. Uses a tiny LCG so every run
+/// tests the exact same positions.
+fn build_probes(walls_per_side: usize) -> Vec<[Point; 4]> {
+    let extent = walls_per_side as f32 * WALL_SPACING;
+    let mut seed: u32 = 12345;
+    let mut next = || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        (seed >> 8) as f32 / (1u32 << 24) as f32 * extent
+    };
+
+    (0..QUERIES)
+        .map(|_| {
+            let x = next();
+            let y = next();
+            [
+                Point::new(x - 30.0, y - 40.0),
+                Point::new(x + 30.0, y - 40.0),
+                Point::new(x + 30.0, y + 40.0),
+                Point::new(x - 30.0, y + 40.0),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,337 @@
+//! Makes up a city from a seed: a grid of streets with blocks of different
+//! sizes, buildings on every block, pickup spots along the curbs and a
+//! garage. Comes out as a `Level`, so it plays exactly like a level file
+//! (and the same seed always makes the same city).
+//!
+//! Streets and sidewalks are tiles (see `tilemap.rs`); buildings are walls
+//! set back from the sidewalk, so they aren't stuck to the tile grid.
+
+use crate::constants::TILE_SIZE;
+use crate::level::{Level, Region, RegionKind, Spawn, TileRows, WallDefinition};
+use crate::math::{Point, Rect};
+use crate::rng::Rng;
+use crate::roads::{Road, RoadGraph};
+use crate::tilemap::Tile;
+
+/// Blocks across and down.
+const BLOCKS: usize = 6;
+/// Blocks are between these many tiles across, counting the sidewalk round
+/// the edge.
+const MIN_BLOCK_TILES: usize = 6;
+const MAX_BLOCK_TILES: usize = 11;
+/// Streets are this many tiles wide.
+const ROAD_TILES: usize = 2;
+/// Buildings are set back at least this far from the sidewalk, and this far
+/// apart when a block is split into lots.
+const BUILDING_SETBACK: f32 = 15.0;
+const ALLEY_WIDTH: f32 = 40.0;
+/// Lots smaller than this (either way) aren't split any more.
+const MIN_LOT_SIZE: f32 = 250.0;
+/// Chance of a pickup spot on each side of each block.
+const PICKUP_CHANCE: f32 = 0.2;
+/// Pickup spots and the garage are this big, half on the sidewalk and half
+/// on the street.
+const ZONE_SIZE: f32 = 160.0;
+
+const STREET_NAMES: [&str; 8] = [
+    "Oak", "Elm", "Pine", "Maple", "Cedar", "Birch", "Walnut", "Spruce",
+];
+
+pub fn generate_city(seed: u64) -> Level {
+    let mut rng = Rng::new(seed);
+
+    let column_widths: Vec<usize> = (0..BLOCKS).map(|_| block_tiles(&mut rng)).collect();
+    let row_heights: Vec<usize> = (0..BLOCKS).map(|_| block_tiles(&mut rng)).collect();
+    // Tile where each street starts, left to right (or top to bottom), with
+    // the city roughly centered on the origin.
+    let column_streets = street_starts(&column_widths);
+    let row_streets = street_starts(&row_heights);
+
+    let origin = (column_streets[0], row_streets[0]);
+    let rows = (0..total_tiles(&row_heights) as i32)
+        .map(|y| {
+            (0..total_tiles(&column_widths) as i32)
+                .map(|x| {
+                    let column = block_span(&column_streets, &column_widths, origin.0 + x);
+                    let row = block_span(&row_streets, &row_heights, origin.1 + y);
+                    let tile = match (column, row) {
+                        (Some(column), Some(row)) => {
+                            let edge = |(start, end): (i32, i32), tile: i32| {
+                                tile == start || tile == end - 1
+                            };
+                            if edge(column, origin.0 + x) || edge(row, origin.1 + y) {
+                                Tile::Sidewalk
+                            } else {
+                                Tile::Empty
+                            }
+                        }
+                        _ => Tile::Road,
+                    };
+                    tile.to_char()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut walls = Vec::new();
+    let mut curbs = Vec::new();
+    for (block_y, height) in row_heights.iter().enumerate() {
+        for (block_x, width) in column_widths.iter().enumerate() {
+            let block = Rect::new(
+                (column_streets[block_x] + ROAD_TILES as i32) as f32 * TILE_SIZE,
+                (row_streets[block_y] + ROAD_TILES as i32) as f32 * TILE_SIZE,
+                *width as f32 * TILE_SIZE,
+                *height as f32 * TILE_SIZE,
+            );
+            // Inside the sidewalk, and then set back a bit.
+            let inside = shrink(&block, TILE_SIZE + BUILDING_SETBACK);
+            split_into_buildings(&inside, &mut rng, &mut walls);
+            curbs.extend(block_curbs(&block, block_x, block_y));
+        }
+    }
+
+    let roads = street_grid(&column_streets, &row_streets);
+    // Start in the middle, on the intersection closest to the origin.
+    let spawn = roads
+        .nodes
+        .iter()
+        .copied()
+        .min_by(|a, b| a.length().total_cmp(&b.length()))
+        .unwrap_or(Point::new(0.0, 0.0));
+
+    Level {
+        name: format!("City {}", seed),
+        spawn: Spawn {
+            position: spawn,
+            heading: 0.0,
+        },
+        walls,
+        regions: curb_regions(curbs, &mut rng),
+        roads,
+        lanes: Vec::new(),
+        tiles: TileRows { origin, rows },
+    }
+}
+
+fn block_tiles(rng: &mut Rng) -> usize {
+    MIN_BLOCK_TILES + rng.index(MAX_BLOCK_TILES - MIN_BLOCK_TILES + 1)
+}
+
+/// First and last (exclusive) tile of whichever block `tile` is in, if it's
+/// not in a street.
+fn block_span(streets: &[i32], block_sizes: &[usize], tile: i32) -> Option<(i32, i32)> {
+    streets
+        .iter()
+        .zip(block_sizes)
+        .map(|(street, size)| {
+            let start = street + ROAD_TILES as i32;
+            (start, start + *size as i32)
+        })
+        .find(|(start, end)| (*start..*end).contains(&tile))
+}
+
+/// Streets go either side of every block.
+fn total_tiles(block_sizes: &[usize]) -> usize {
+    block_sizes.iter().sum::<usize>() + ROAD_TILES * (block_sizes.len() + 1)
+}
+
+fn street_starts(block_sizes: &[usize]) -> Vec<i32> {
+    let mut start = -(total_tiles(block_sizes) as i32 / 2);
+    let mut streets = vec![start];
+    for size in block_sizes {
+        start += (size + ROAD_TILES) as i32;
+        streets.push(start);
+    }
+    streets
+}
+
+/// Nodes down the middle of every street where they cross, joined to the
+/// next one along each way.
+fn street_grid(column_streets: &[i32], row_streets: &[i32]) -> RoadGraph {
+    let middle = |street: i32| (street as f32 + ROAD_TILES as f32 / 2.0) * TILE_SIZE;
+    let columns = column_streets.len();
+    let mut roads = RoadGraph::default();
+
+    for (row, row_street) in row_streets.iter().enumerate() {
+        for (column, column_street) in column_streets.iter().enumerate() {
+            let node = roads.nodes.len();
+            roads
+                .nodes
+                .push(Point::new(middle(*column_street), middle(*row_street)));
+            if column > 0 {
+                roads.edges.push(Road::new(node - 1, node));
+            }
+            if row > 0 {
+                roads.edges.push(Road::new(node - columns, node));
+            }
+        }
+    }
+    roads
+}
+
+fn shrink(rect: &Rect, amount: f32) -> Rect {
+    Rect::new(
+        rect.x + amount,
+        rect.y + amount,
+        rect.w - amount * 2.0,
+        rect.h - amount * 2.0,
+    )
+}
+
+/// Cuts `lot` in two across its longer side (with an alley in between)
+/// until the pieces are small, then puts a building on each.
+fn split_into_buildings(lot: &Rect, rng: &mut Rng, walls: &mut Vec<WallDefinition>) {
+    let splittable = lot.w.max(lot.h) >= MIN_LOT_SIZE * 2.0 + ALLEY_WIDTH;
+    // Big lots usually split, but not always, so some buildings are big.
+    if splittable && rng.next_f32() < 0.8 {
+        let fraction = rng.range_f32(0.35, 0.65);
+        let (first, second) = if lot.w >= lot.h {
+            let width = (lot.w - ALLEY_WIDTH) * fraction;
+            (
+                Rect::new(lot.x, lot.y, width, lot.h),
+                Rect::new(
+                    lot.x + width + ALLEY_WIDTH,
+                    lot.y,
+                    lot.w - width - ALLEY_WIDTH,
+                    lot.h,
+                ),
+            )
+        } else {
+            let height = (lot.h - ALLEY_WIDTH) * fraction;
+            (
+                Rect::new(lot.x, lot.y, lot.w, height),
+                Rect::new(
+                    lot.x,
+                    lot.y + height + ALLEY_WIDTH,
+                    lot.w,
+                    lot.h - height - ALLEY_WIDTH,
+                ),
+            )
+        };
+        split_into_buildings(&first, rng, walls);
+        split_into_buildings(&second, rng, walls);
+        return;
+    }
+
+    walls.push(WallDefinition {
+        points: building_footprint(lot, rng),
+        rotation: 0.0,
+    });
+}
+
+/// A box filling most of `lot`, sometimes with a corner taken out of it.
+fn building_footprint(lot: &Rect, rng: &mut Rng) -> Vec<Point> {
+    let mut inset = || rng.range_f32(0.0, 0.12);
+    let left = lot.x + lot.w * inset();
+    let top = lot.y + lot.h * inset();
+    let right = lot.x + lot.w * (1.0 - inset());
+    let bottom = lot.y + lot.h * (1.0 - inset());
+
+    if rng.next_f32() > 0.3 {
+        return vec![
+            Point::new(left, top),
+            Point::new(right, top),
+            Point::new(right, bottom),
+            Point::new(left, bottom),
+        ];
+    }
+
+    // L shaped: the bottom right corner is cut out.
+    let cut_x = left + (right - left) * rng.range_f32(0.4, 0.7);
+    let cut_y = top + (bottom - top) * rng.range_f32(0.4, 0.7);
+    vec![
+        Point::new(left, top),
+        Point::new(right, top),
+        Point::new(right, cut_y),
+        Point::new(cut_x, cut_y),
+        Point::new(cut_x, bottom),
+        Point::new(left, bottom),
+    ]
+}
+
+/// Somewhere along the curb of a block that could be a pickup spot.
+struct Curb {
+    /// Where the sidewalk meets the street, halfway along the block.
+    center: Point,
+    /// Street name with a house number, unique across the city.
+    address: String,
+}
+
+fn block_curbs(block: &Rect, block_x: usize, block_y: usize) -> [Curb; 4] {
+    // Avenues go down (numbered), streets go across (named). Numbers go up
+    // by 100 a block, odd on one side of the road and even on the other.
+    let avenue = |index: usize| {
+        let number = index + 1;
+        let suffix = match number {
+            1 => "st",
+            2 => "nd",
+            3 => "rd",
+            _ => "th",
+        };
+        format!("{}{} Ave", number, suffix)
+    };
+    let street = |index: usize| {
+        let name = STREET_NAMES[index % STREET_NAMES.len()];
+        // Only once the names run out.
+        let lap = index / STREET_NAMES.len();
+        if lap == 0 {
+            format!("{} St", name)
+        } else {
+            format!("{} St {}", name, lap + 1)
+        }
+    };
+    let along_street = (block_x + 1) * 100;
+    let along_avenue = (block_y + 1) * 100;
+    let center = block.center();
+
+    [
+        Curb {
+            center: Point::new(center.x, block.y),
+            address: format!("{} {}", along_street + 1, street(block_y)),
+        },
+        Curb {
+            center: Point::new(center.x, block.y + block.h),
+            address: format!("{} {}", along_street, street(block_y + 1)),
+        },
+        Curb {
+            center: Point::new(block.x, center.y),
+            address: format!("{} {}", along_avenue + 1, avenue(block_x)),
+        },
+        Curb {
+            center: Point::new(block.x + block.w, center.y),
+            address: format!("{} {}", along_avenue, avenue(block_x + 1)),
+        },
+    ]
+}
+
+/// Picks some curbs to be pickup spots, and one to be the garage. Always at
+/// least two pickups, so there's somewhere to go.
+fn curb_regions(mut curbs: Vec<Curb>, rng: &mut Rng) -> Vec<Region> {
+    let zone = |curb: &Curb, name: String, kind| Region {
+        name,
+        area: Rect::new(
+            curb.center.x - ZONE_SIZE / 2.0,
+            curb.center.y - ZONE_SIZE / 2.0,
+            ZONE_SIZE,
+            ZONE_SIZE,
+        ),
+        kind,
+    };
+
+    let garage = curbs.swap_remove(rng.index(curbs.len()));
+    let mut regions = vec![zone(&garage, "Garage".to_owned(), RegionKind::Garage)];
+
+    for curb in curbs.iter() {
+        if rng.next_f32() < PICKUP_CHANCE {
+            regions.push(zone(curb, curb.address.clone(), RegionKind::Pickup));
+        }
+    }
+    while regions.len() < 3 {
+        let curb = curbs.swap_remove(rng.index(curbs.len()));
+        if !regions.iter().any(|region| region.name == curb.address) {
+            regions.push(zone(&curb, curb.address.clone(), RegionKind::Pickup));
+        }
+    }
+
+    regions
+}
@@ -0,0 +1,199 @@
+// These are the defaults; see `tuning.rs` for changing them without
+// recompiling.
+
+use serde::{Deserialize, Serialize};
+
+/// Simulation ticks per second. Rendering runs at whatever rate vsync allows
+/// and interpolates between ticks.
+pub const SIMULATION_TICK_RATE: f32 = 120.0;
+
+// Gas/dash/reverse are how much velocity is gained per second of holding gas.
+// (These used to be per frame; the values are the old ones at 120 fps.)
+pub const GAS_VELOCITY: f32 = 960.0;
+pub const CRAZY_DASH_VELOCITY: f32 = 3600.0;
+pub const REVERSE_VELOCITY: f32 = 2400.0;
+
+pub const CRAZY_DASH_LENGTH: f64 = 0.3;
+pub const CRAZY_DASH_MAX_VELOCITY: f32 = 2300.0;
+
+pub const PLAYER_MAX_VELOCITY: f32 = 750.0;
+//pub const PLAYER_MAX_VELOCITY: f32 = 500.0;
+pub const PLAYER_MAX_REVERSE_VELOCITY: f32 = -350.0;
+// Old rotation before improving turn behavior to be slower if not moving fast.
+//pub const PLAYER_ROTATION_SPEED: f32 = 125.0;
+pub const PLAYER_ROTATION_SPEED: f32 = 10.0;
+
+/// Reverse won't engage while going forward faster than this.
+pub const REVERSE_ENGAGE_MAX_SPEED: f32 = 50.0;
+/// Park won't engage while going faster than this.
+pub const PARK_ENGAGE_MAX_SPEED: f32 = 5.0;
+
+/// Velocity lost per second with the brake fully on.
+pub const BRAKE_DECELERATION: f32 = 1500.0;
+/// Fraction of `BRAKE_DECELERATION` the moment the brake is pressed...
+pub const BRAKE_INITIAL_STRENGTH: f32 = 0.4;
+/// ...ramping up to full over this many seconds of holding it.
+pub const BRAKE_RAMP_TIME: f32 = 0.3;
+
+// Tires cancel out sideways (lateral) velocity up to this much per second.
+// If a turn needs more than that the car breaks loose and drifts, and while
+// sliding it only gets `TIRE_SLIDING_GRIP` until it's back under control.
+pub const TIRE_GRIP: f32 = 1200.0;
+pub const TIRE_SLIDING_GRIP: f32 = 700.0;
+
+pub const CAR_DEFAULT_DRAG: f32 = 500.0;
+//pub const CRAZY_DASH_DRAG: f32 = 5.0;
+pub const CRAZY_DASH_DRAG: f32 = 50.0;
+/// How quickly drag goes back to normal after a crazy dash (scaled down by
+/// the current velocity).
+pub const CRAZY_DASH_DRAG_RECOVERY: f32 = 120_000.0;
+pub const CRAZY_DASH_INPUT_TIMING: std::ops::Range<f64> = 0.04..0.09;
+/// Hitting the gas this soon after drive (inside `CRAZY_DASH_INPUT_TIMING`)
+/// is a perfect dash, which is what keeps a chain going.
+pub const CRAZY_DASH_PERFECT_TIMING: std::ops::Range<f64> = 0.045..0.07;
+/// Seconds after a dash ends before the next one can start.
+pub const CRAZY_DASH_COOLDOWN: f64 = 0.25;
+/// A perfect dash within this many seconds of the last one ending adds to
+/// the chain...
+pub const CRAZY_DASH_CHAIN_WINDOW: f64 = 1.5;
+/// ...and each dash in the chain after the first pushes this much harder
+/// (and faster)...
+pub const CRAZY_DASH_CHAIN_BONUS: f32 = 0.2;
+/// ...up to this many in a row.
+pub const CRAZY_DASH_MAX_CHAIN: u32 = 4;
+/// Boost meter goes from 0.0 to 1.0. Each dash uses this much of it...
+pub const CRAZY_DASH_BOOST_COST: f32 = 0.35;
+/// ...and it refills this much per second when not dashing.
+pub const CRAZY_DASH_BOOST_REGEN: f32 = 0.15;
+
+/// How much speed is kept (and bounced back) when hitting a wall head on.
+/// 0.0 stops the car dead, 1.0 is a perfect bounce.
+pub const WALL_RESTITUTION: f32 = 0.3;
+
+/// Passengers only get in/out once the taxi is going slower than this.
+pub const PASSENGER_PICKUP_MAX_SPEED: f32 = 10.0;
+/// Seconds between new passengers showing up.
+pub const PASSENGER_SPAWN_INTERVAL: f64 = 5.0;
+pub const MAX_WAITING_PASSENGERS: usize = 2;
+/// A shift is this many seconds (of simulated time) long. Once it's up the
+/// fares get added up and that's the end of the run.
+pub const SHIFT_LENGTH: f64 = 300.0;
+/// Seed for anything random in the simulation (like where passengers want to
+/// go), so every run plays out the same for the same inputs.
+pub const RNG_SEED: u64 = 0x7a41;
+
+// Fare = (base + distance) scaled down if the ride took longer than par time,
+// minus a penalty for crashing with the passenger in the car.
+pub const FARE_BASE: f32 = 5.0;
+/// Per pixel between the pickup and the destination.
+pub const FARE_PER_DISTANCE: f32 = 0.01;
+/// Par time is the distance at this speed, plus `FARE_GRACE_TIME`.
+pub const FARE_EXPECTED_SPEED: f32 = 300.0;
+pub const FARE_GRACE_TIME: f64 = 3.0;
+/// Per point of health the taxi lost during the ride.
+pub const FARE_DAMAGE_PENALTY: f32 = 0.25;
+
+// Cars start on `MAX_HEALTH` and lose some in every crash. At 0.0 they're
+// wrecked and the engine won't do anything.
+pub const MAX_HEALTH: f32 = 100.0;
+/// Impacts slower than this are just scrapes and don't do any damage...
+pub const DAMAGE_MIN_IMPACT_SPEED: f32 = 100.0;
+/// ...anything faster loses this much health per unit of impact speed over it.
+pub const DAMAGE_PER_IMPACT_SPEED: f32 = 0.04;
+/// Health a garage fixes per second while the taxi is stopped in it...
+pub const REPAIR_RATE: f32 = 25.0;
+/// ...and how much each point of it costs.
+pub const REPAIR_COST: f32 = 0.05;
+/// Has to be going slower than this to count as stopped in a garage.
+pub const REPAIR_MAX_SPEED: f32 = 10.0;
+/// Seconds after being wrecked that the taxi gets towed to the closest
+/// garage (traffic gets towed too, back onto its lane)...
+pub const TOW_DELAY: f64 = 3.0;
+/// ...for this much.
+pub const TOW_COST: f32 = 10.0;
+
+// Points for driving with style (see `scoring.rs`), multiplied by the combo.
+/// For a perfectly timed dash, times the dash chain...
+pub const PERFECT_DASH_POINTS: f32 = 100.0;
+/// ...plus up to this much more for hitting the middle of the perfect window.
+pub const PERFECT_DASH_BULLSEYE_POINTS: f32 = 50.0;
+/// Passing a wall with less than this gap (between the outlines) is a near
+/// miss...
+pub const NEAR_MISS_DISTANCE: f32 = 40.0;
+/// ...as long as the taxi's going at least this fast...
+pub const NEAR_MISS_MIN_SPEED: f32 = 400.0;
+/// ...for this much, or up to twice it for only just missing.
+pub const NEAR_MISS_POINTS: f32 = 150.0;
+/// Drifts shorter than this don't count...
+pub const DRIFT_MIN_TIME: f64 = 0.75;
+/// ...longer ones get this much per second of drifting.
+pub const DRIFT_POINTS_PER_SECOND: f32 = 80.0;
+/// Picking up a passenger within `HIGH_SPEED_PICKUP_WINDOW` seconds of going
+/// this fast is a high speed pickup...
+pub const HIGH_SPEED_PICKUP_SPEED: f32 = 500.0;
+pub const HIGH_SPEED_PICKUP_WINDOW: f64 = 2.0;
+/// ...and gets this much.
+pub const HIGH_SPEED_PICKUP_POINTS: f32 = 250.0;
+/// Every trick adds this to the combo multiplier, up to `COMBO_MAX`...
+pub const COMBO_STEP: f32 = 0.5;
+pub const COMBO_MAX: f32 = 5.0;
+/// ...which stays put for this many seconds after the last trick...
+pub const COMBO_HOLD_TIME: f64 = 2.0;
+/// ...then drops back towards 1.0 by this much a second. Crashing hard
+/// enough to do damage drops it straight back to 1.0.
+pub const COMBO_DECAY_RATE: f32 = 1.0;
+
+// Traffic cars drive like the taxi (same physics, and `GameState::set_tuning`
+// tunes them all the same), these are just for how the AI drives them.
+/// Speed traffic drives at on a straight, clear road.
+pub const TRAFFIC_CRUISE_SPEED: f32 = 300.0;
+/// Corners are taken at down to this fraction of the cruise speed.
+pub const TRAFFIC_CORNER_SLOWDOWN: f32 = 0.4;
+/// A waypoint counts as reached this close to it.
+pub const TRAFFIC_WAYPOINT_RADIUS: f32 = 80.0;
+/// Steering per radian the waypoint is off to the side (clamped to full lock).
+pub const TRAFFIC_STEER_GAIN: f32 = 2.0;
+/// Cars further ahead than this are ignored...
+pub const TRAFFIC_LOOK_AHEAD: f32 = 350.0;
+/// ...and traffic stops completely when one is this close (center to center).
+pub const TRAFFIC_STOP_DISTANCE: f32 = 150.0;
+/// How far to the side a car can be and still count as in the way.
+pub const TRAFFIC_LANE_HALF_WIDTH: f32 = 50.0;
+
+/// Roads in the level are this many lanes (both ways together)...
+pub const ROAD_LANES: u32 = 2;
+/// ...this wide, unless they say otherwise.
+pub const ROAD_LANE_WIDTH: f32 = 80.0;
+
+/// Level geometry is expected to fit in a square this far out from the
+/// origin. Anything outside still works, it just isn't spatially indexed.
+pub const WORLD_HALF_SIZE: f32 = 10_000.0;
+
+/// Width and height of one tile in the tilemap, in world units.
+pub const TILE_SIZE: f32 = 100.0;
+/// Chunks are this many tiles across (and down).
+pub const CHUNK_TILES: usize = 16;
+/// Buildings in chunks within this far of the taxi (either way, in world
+/// units) have their walls loaded. Covers the whole screen even zoomed all
+/// the way out, so nothing pops in.
+pub const TILEMAP_STREAM_DISTANCE: f32 = 3200.0;
+/// Traffic only needs the buildings it could hit, so just the chunks within
+/// this far of each car. Plenty more than a car moves in a tick.
+pub const TILEMAP_TRAFFIC_STREAM_DISTANCE: f32 = TILE_SIZE * 2.0;
+
+// this is extra but was trying to improve the function where vertices are created
+// TODO could revert this tbh
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerShapeConfig {
+    pub half_width: f32,
+    pub half_height: f32,
+    pub inner_space_w: f32,
+    pub inner_space_h: f32,
+}
+
+pub const PLAYER_SHAPE: PlayerShapeConfig = PlayerShapeConfig {
+    half_width: 30.0,
+    half_height: 40.0,
+    inner_space_w: 10.0,
+    inner_space_h: 15.0,
+};
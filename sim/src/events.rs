@@ -0,0 +1,66 @@
+//! Things that happened during a simulation tick, for anything outside the
+//! simulation that wants to react to them (sounds, camera shake, effects,
+//! stats) without polling the taxi every frame to work out what changed.
+//!
+//! `simulate` clears `GameState::events` at the start of every tick and fills
+//! it back up as things happen, in the order they happened. When a frame runs
+//! more than one tick, read the events after each one.
+
+use crate::math::Point;
+use crate::passenger::Fare;
+use crate::player::{DashDenied, DashTiming, ShiftMode};
+use crate::scoring::Trick;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    DashStarted {
+        timing: DashTiming,
+        /// See `Player::crazy_dash_chain`.
+        chain: u32,
+        /// See `Player::crazy_dash_strength`.
+        strength: f32,
+    },
+    DashEnded,
+    /// The gas was timed right for a dash, but it didn't happen.
+    DashDenied(DashDenied),
+    GearChanged {
+        from: ShiftMode,
+        to: ShiftMode,
+    },
+    /// The taxi hit something. One for each part of a wall or car it hit.
+    Collision {
+        /// Points away from whatever was hit.
+        normal: Point,
+        /// How fast the taxi was going into it along `normal`, i.e. how hard
+        /// it hit. Always positive; scrapes that don't bounce the taxi don't
+        /// count as collisions.
+        impact_speed: f32,
+        with: Obstacle,
+    },
+    /// Health ran out.
+    Wrecked,
+    /// Moved to a garage after being wrecked, or fixed in place if there
+    /// isn't one.
+    Towed,
+    PassengerPickedUp {
+        pickup: String,
+        destination: String,
+    },
+    PassengerDroppedOff(Fare),
+    /// Points for a trick, see `scoring.rs`.
+    Scored {
+        trick: Trick,
+        /// Already multiplied by `combo`, and added to `Scoring::total`.
+        points: u32,
+        combo: f32,
+        /// Where the taxi was, for showing the points there.
+        position: Point,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Obstacle {
+    Wall,
+    /// Index into `GameState::traffic`.
+    Traffic(usize),
+}
@@ -0,0 +1,472 @@
+use crate::constants::{
+    MAX_HEALTH, REPAIR_COST, REPAIR_MAX_SPEED, REPAIR_RATE, RNG_SEED, SHIFT_LENGTH,
+    TILEMAP_STREAM_DISTANCE, TILEMAP_TRAFFIC_STREAM_DISTANCE, TOW_COST, TOW_DELAY, WORLD_HALF_SIZE,
+};
+use crate::events::{GameEvent, Obstacle};
+use crate::input::InputFrame;
+use crate::level::{Lane, Level, Region, RegionKind};
+use crate::math::{Point, Rect, polygon_collision};
+use crate::passenger::{Fare, Passenger, PassengerState, simulate_passengers};
+use crate::player::Player;
+use crate::quadtree::Quadtree;
+use crate::rng::Rng;
+use crate::roads::{RoadGraph, Route};
+use crate::scoring::{Scoring, simulate_scoring};
+use crate::tilemap::{ChunkCoord, Tilemap};
+use crate::traffic::{TrafficCar, simulate_traffic, spawn_traffic};
+use crate::tuning::Tuning;
+use crate::wall::Wall;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct GameState {
+    pub player: Player,
+
+    /// Seconds of simulated time. Only moves forward when `simulate` runs, so
+    /// anything timing related in the simulation should use this instead of
+    /// the wall clock.
+    pub time: f64,
+
+    /// Named areas from the level.
+    pub regions: Vec<Region>,
+
+    /// From the level, for directions.
+    pub roads: RoadGraph,
+
+    /// Loops that `traffic` drives around, from the level.
+    pub lanes: Vec<Lane>,
+    pub traffic: Vec<TrafficCar>,
+
+    /// Waiting for a ride, or in the taxi (at most one).
+    pub passengers: Vec<Passenger>,
+    /// Every ride finished so far, oldest first.
+    pub fares: Vec<Fare>,
+    pub money: f32,
+    /// Points for tricks this run, see `scoring.rs`.
+    pub scoring: Scoring,
+    pub next_passenger_at: f64,
+    pub rng: Rng,
+    /// Everything that happened on the last tick, see `events.rs`.
+    pub events: Vec<GameEvent>,
+    /// When the taxi was wrecked, if it is. It gets towed to a garage a
+    /// little while after.
+    pub wrecked_at: Option<f64>,
+
+    /// From the level. Buildings near the taxi are loaded into `walls`, see
+    /// `stream_tilemap`.
+    pub tilemap: Tilemap,
+    /// Chunks with their buildings in `walls`, and the indexes of those walls.
+    loaded_chunks: BTreeMap<ChunkCoord, Vec<usize>>,
+
+    walls: Vec<Wall>,
+    /// Broadphase over `walls`, keyed by index.
+    wall_index: Quadtree<usize>,
+}
+
+impl GameState {
+    pub fn new(player: Player) -> Self {
+        let mut game_state = Self::empty(player);
+
+        // for testing
+        let player_center = game_state.player.center;
+        game_state.add_wall(Wall::new(player_center.x, player_center.y + 300.0));
+
+        game_state
+    }
+
+    /// Builds the world from a (validated) level.
+    pub fn from_level(level: &Level) -> Self {
+        let spawn = &level.spawn;
+        let mut player = Player::new(spawn.position.x, spawn.position.y);
+        player.set_rotation(spawn.heading.to_radians());
+
+        let mut game_state = Self::empty(player);
+        for wall in level.walls.iter() {
+            game_state.add_wall(Wall::from_points(
+                wall.points.clone(),
+                wall.rotation.to_radians(),
+            ));
+        }
+        game_state.regions = level.regions.clone();
+        game_state.roads = level.roads.clone();
+        game_state.lanes = level.lanes.clone();
+        // Already checked by `Level::validate`.
+        game_state.tilemap = level.tiles.to_tilemap().unwrap_or_default();
+        spawn_traffic(&mut game_state);
+        stream_tilemap_around_cars(&mut game_state);
+
+        game_state
+    }
+
+    /// Everything in the world is expected to be inside this.
+    pub fn world_bounds() -> Rect {
+        Rect::new(
+            -WORLD_HALF_SIZE,
+            -WORLD_HALF_SIZE,
+            WORLD_HALF_SIZE * 2.0,
+            WORLD_HALF_SIZE * 2.0,
+        )
+    }
+
+    /// Tuning for every car, traffic too, so they all drive the same.
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        for traffic_car in self.traffic.iter_mut() {
+            traffic_car.car.set_tuning(tuning.clone());
+        }
+        self.player.set_tuning(tuning);
+    }
+
+    fn empty(player: Player) -> Self {
+        Self {
+            player,
+            time: 0.0,
+            regions: Vec::new(),
+            roads: RoadGraph::default(),
+            lanes: Vec::new(),
+            traffic: Vec::new(),
+            passengers: Vec::new(),
+            fares: Vec::new(),
+            money: 0.0,
+            scoring: Scoring::new(),
+            next_passenger_at: 0.0,
+            rng: Rng::new(RNG_SEED),
+            events: Vec::new(),
+            wrecked_at: None,
+            tilemap: Tilemap::new(),
+            loaded_chunks: BTreeMap::new(),
+            walls: Vec::new(),
+            wall_index: Quadtree::new(Self::world_bounds()),
+        }
+    }
+
+    /// Seconds until the shift's over, 0.0 once it is.
+    pub fn shift_time_left(&self) -> f64 {
+        (SHIFT_LENGTH - self.time).max(0.0)
+    }
+
+    /// Nothing stops `simulate` running after this, it's up to whatever's
+    /// running the game to stop.
+    pub fn is_shift_over(&self) -> bool {
+        self.time >= SHIFT_LENGTH
+    }
+
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+
+    pub fn riding_passenger_index(&self) -> Option<usize> {
+        self.passengers
+            .iter()
+            .position(|passenger| matches!(passenger.state, PassengerState::Riding { .. }))
+    }
+
+    pub fn riding_passenger(&self) -> Option<&Passenger> {
+        self.riding_passenger_index()
+            .map(|index| &self.passengers[index])
+    }
+
+    /// Where the taxi should be heading: the riding passenger's destination,
+    /// otherwise the closest passenger waiting for a ride.
+    pub fn destination(&self) -> Option<Point> {
+        if let Some(passenger) = self.riding_passenger() {
+            return self
+                .region(&passenger.destination)
+                .map(|region| region.area.center());
+        }
+
+        let taxi_center = self.player.center;
+        self.passengers
+            .iter()
+            .filter_map(|passenger| self.region(&passenger.pickup))
+            .map(|region| region.area.center())
+            .min_by(|a, b| {
+                let a_distance = (*a - taxi_center).length();
+                let b_distance = (*b - taxi_center).length();
+                a_distance.total_cmp(&b_distance)
+            })
+    }
+
+    /// Directions along the roads from the taxi to `destination`.
+    pub fn route_to_destination(&self) -> Option<Route> {
+        self.roads
+            .find_route(&self.player.center, &self.destination()?)
+    }
+
+    pub fn walls(&self) -> &[Wall] {
+        &self.walls
+    }
+
+    /// Returns the index of the new wall.
+    pub fn add_wall(&mut self, wall: Wall) -> usize {
+        let index = self.walls.len();
+        self.wall_index.insert(index, wall.bounds());
+        self.walls.push(wall);
+        index
+    }
+
+    /// Removes the wall at `index`. The last wall takes its place, so
+    /// indexes from before this call may no longer be valid. Don't use this
+    /// on walls from the tilemap, they're handled by `stream_tilemap`.
+    pub fn remove_wall(&mut self, index: usize) -> Wall {
+        let last_index = self.walls.len() - 1;
+        self.wall_index.remove(index, &self.walls[index].bounds());
+        if index != last_index {
+            let last_bounds = self.walls[last_index].bounds();
+            self.wall_index.remove(last_index, &last_bounds);
+            self.wall_index.insert(index, last_bounds);
+        }
+        self.walls.swap_remove(index)
+    }
+
+    /// Loads the buildings of every chunk overlapping any of `areas` as
+    /// walls, and unloads any other chunk's. `simulate` does this around the
+    /// taxi and traffic every tick, so it only needs calling directly to load
+    /// somewhere else.
+    pub fn stream_tilemap(&mut self, areas: &[Rect]) {
+        let wanted: BTreeSet<ChunkCoord> = areas
+            .iter()
+            .flat_map(|area| self.tilemap.chunks_in(area))
+            .map(|(coord, _)| coord)
+            .collect();
+
+        let unwanted: Vec<ChunkCoord> = self
+            .loaded_chunks
+            .keys()
+            .filter(|coord| !wanted.contains(coord))
+            .copied()
+            .collect();
+        for coord in unwanted {
+            self.unload_chunk(coord);
+        }
+
+        for coord in wanted {
+            if self.loaded_chunks.contains_key(&coord) {
+                continue;
+            }
+            let Some(chunk) = self.tilemap.chunk(coord) else {
+                continue;
+            };
+            let walls: Vec<usize> = chunk
+                .building_outlines(coord)
+                .into_iter()
+                .map(|outline| self.add_wall(Wall::from_points(outline, 0.0)))
+                .collect();
+            self.loaded_chunks.insert(coord, walls);
+        }
+    }
+
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = &ChunkCoord> {
+        self.loaded_chunks.keys()
+    }
+
+    fn unload_chunk(&mut self, coord: ChunkCoord) {
+        let Some(mut walls) = self.loaded_chunks.remove(&coord) else {
+            return;
+        };
+
+        // Highest first, so `remove_wall` moving the last wall down never
+        // moves one of this chunk's that's still to be removed.
+        walls.sort_unstable_by(|a, b| b.cmp(a));
+        for index in walls {
+            let last_index = self.walls.len() - 1;
+            self.remove_wall(index);
+            if index == last_index {
+                continue;
+            }
+            // Whichever chunk owned the last wall needs to know where it went.
+            for other in self.loaded_chunks.values_mut() {
+                if let Some(moved) = other.iter_mut().find(|wall| **wall == last_index) {
+                    *moved = index;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Hash of everything that moves, used by replays to check that a run
+    /// plays out exactly the same way (see `Player::checksum`).
+    pub fn checksum(&self) -> u64 {
+        let mut hash = self.player.checksum();
+        for traffic_car in self.traffic.iter() {
+            // Order matters, so car A then B isn't the same as B then A.
+            hash = hash.rotate_left(5) ^ traffic_car.car.checksum();
+        }
+        hash
+    }
+
+    /// Pushes `car` out of any walls it's in. Returns a `Collision` for
+    /// each one it hit hard enough to bounce off.
+    pub(crate) fn resolve_wall_collisions(&self, car: &mut Player) -> Vec<GameEvent> {
+        resolve_wall_collisions(&self.walls, &self.wall_index, car)
+    }
+
+    /// Walls whose bounding box overlaps `area`.
+    pub fn walls_in(&self, area: &Rect) -> impl Iterator<Item = &Wall> {
+        self.wall_index
+            .query(area)
+            .into_iter()
+            .map(|index| &self.walls[index])
+    }
+}
+
+/// Runs a single simulation tick. Expected to be called with a fixed
+/// `delta_time` (see `FixedTimestep`) so results don't depend on frame rate.
+pub fn simulate(input_frame: &InputFrame, game_state: &mut GameState, delta_time: f32) {
+    game_state.events.clear();
+    let health_before = game_state.player.health;
+    simulate_player(input_frame, game_state, delta_time);
+    simulate_traffic(game_state, delta_time);
+    take_player_events(game_state);
+    damage_riding_passenger(game_state, health_before - game_state.player.health);
+    tow_wrecked_taxi(game_state);
+    take_player_events(game_state);
+    repair_at_garages(game_state, delta_time);
+    simulate_passengers(game_state);
+    simulate_scoring(game_state, delta_time);
+    game_state.time += delta_time as f64;
+}
+
+fn simulate_player(input_frame: &InputFrame, game_state: &mut GameState, delta_time: f32) {
+    game_state.player.save_previous_position();
+
+    for player_action in input_frame.player_actions.iter() {
+        game_state
+            .player
+            .apply_action(player_action, game_state.time, delta_time);
+    }
+    // After any teleporting, before anything can hit a wall.
+    stream_tilemap_around_cars(game_state);
+
+    game_state.player.simulate(delta_time, game_state.time);
+    take_player_events(game_state);
+
+    let collisions = resolve_wall_collisions(
+        &game_state.walls,
+        &game_state.wall_index,
+        &mut game_state.player,
+    );
+    game_state.events.extend(collisions);
+    take_player_events(game_state);
+}
+
+/// Streaming follows the taxi rather than the camera, so which walls exist
+/// (and so how a replay plays out) doesn't depend on the window size. Traffic
+/// gets the buildings right around it loaded too, wherever it is, so it
+/// can't drive through ones that aren't loaded yet.
+fn stream_tilemap_around_cars(game_state: &mut GameState) {
+    if game_state.tilemap.is_empty() {
+        return;
+    }
+    let mut areas = vec![square_around(
+        &game_state.player.center,
+        TILEMAP_STREAM_DISTANCE,
+    )];
+    for traffic_car in game_state.traffic.iter() {
+        areas.push(square_around(
+            &traffic_car.car.center,
+            TILEMAP_TRAFFIC_STREAM_DISTANCE,
+        ));
+    }
+    game_state.stream_tilemap(&areas);
+}
+
+fn square_around(center: &Point, distance: f32) -> Rect {
+    Rect::new(
+        center.x - distance,
+        center.y - distance,
+        distance * 2.0,
+        distance * 2.0,
+    )
+}
+
+/// Moves whatever the taxi's been up to onto the tick's events, keeping them
+/// in order with everything else.
+fn take_player_events(game_state: &mut GameState) {
+    game_state.events.append(&mut game_state.player.events);
+}
+
+fn resolve_wall_collisions(
+    walls: &[Wall],
+    wall_index: &Quadtree<usize>,
+    car: &mut Player,
+) -> Vec<GameEvent> {
+    let mut collisions = Vec::new();
+    for index in wall_index.query(&car.bounds()) {
+        for part in walls[index].convex_parts.iter() {
+            let Some(collision) = polygon_collision(&car.points, part) else {
+                continue;
+            };
+            let impact_speed = car.resolve_collision(&collision);
+            if impact_speed > 0.0 {
+                collisions.push(GameEvent::Collision {
+                    normal: collision.normal,
+                    impact_speed,
+                    with: Obstacle::Wall,
+                });
+            }
+        }
+    }
+    collisions
+}
+
+fn damage_riding_passenger(game_state: &mut GameState, damage: f32) {
+    // Passengers don't like being thrown around.
+    if let Some(index) = game_state.riding_passenger_index() {
+        game_state.passengers[index].damage += damage;
+    }
+}
+
+/// Wrecked taxis can't drive to a garage themselves. Levels without one
+/// just get the taxi fixed where it stopped, for the same price.
+fn tow_wrecked_taxi(game_state: &mut GameState) {
+    if !game_state.player.is_wrecked() {
+        game_state.wrecked_at = None;
+        return;
+    }
+    let wrecked_at = *game_state.wrecked_at.get_or_insert(game_state.time);
+    if game_state.time - wrecked_at < TOW_DELAY {
+        return;
+    }
+
+    let taxi_center = game_state.player.center;
+    let closest_garage = game_state
+        .regions
+        .iter()
+        .filter(|region| region.kind == RegionKind::Garage)
+        .map(|region| region.area.center())
+        .min_by(|a, b| {
+            let a_distance = (*a - taxi_center).length();
+            let b_distance = (*b - taxi_center).length();
+            a_distance.total_cmp(&b_distance)
+        });
+    match closest_garage {
+        Some(garage) => game_state.player.reposition(garage.x, garage.y, true),
+        // Nowhere to tow it, so it gets fixed up where it is instead.
+        None => {
+            game_state
+                .player
+                .reposition(taxi_center.x, taxi_center.y, true);
+            game_state.player.repair(MAX_HEALTH);
+        }
+    }
+    game_state.money -= TOW_COST;
+    game_state.wrecked_at = None;
+    game_state.events.push(GameEvent::Towed);
+}
+
+/// Stopping in a garage fixes the taxi up, paid for out of `money` (which
+/// can go negative, so a wreck with no money can still be fixed).
+fn repair_at_garages(game_state: &mut GameState, delta_time: f32) {
+    let player = &game_state.player;
+    if player.speed() >= REPAIR_MAX_SPEED {
+        return;
+    }
+    let in_garage = game_state.regions.iter().any(|region| {
+        region.kind == RegionKind::Garage && region.area.contains_point(&player.center)
+    });
+    if !in_garage {
+        return;
+    }
+
+    let repaired = game_state.player.repair(REPAIR_RATE * delta_time);
+    game_state.money -= repaired * REPAIR_COST;
+}
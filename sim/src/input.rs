@@ -0,0 +1,26 @@
+use crate::player::PlayerAction;
+
+/// Everything the player did since the last simulation tick.
+pub struct InputFrame {
+    pub player_actions: Vec<PlayerAction>,
+}
+
+impl InputFrame {
+    pub fn new() -> Self {
+        Self {
+            player_actions: Vec::new(),
+        }
+    }
+
+    /// Call after simulating a tick with this frame, so key presses don't
+    /// get repeated if the same frame is simulated again.
+    pub fn remove_one_shot_actions(&mut self) {
+        self.player_actions.retain(|action| !action.is_one_shot());
+    }
+}
+
+impl Default for InputFrame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
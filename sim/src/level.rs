@@ -0,0 +1,240 @@
+//! Level files describe the world geometry and where the taxi starts.
+//! They're written in RON, e.g.
+//!
+//! ```ron
+//! Level(
+//!     name: "Test block",
+//!     spawn: (position: (x: 0.0, y: 0.0), heading: 90.0),
+//!     walls: [
+//!         (points: [(x: 100.0, y: 0.0), (x: 200.0, y: 0.0), (x: 150.0, y: 80.0)]),
+//!     ],
+//!     regions: [
+//!         (name: "downtown", area: (x: -500.0, y: -500.0, w: 1000.0, h: 1000.0)),
+//!         (name: "station", area: (x: 0.0, y: 300.0, w: 150.0, h: 150.0), kind: Pickup),
+//!     ],
+//!     roads: (
+//!         nodes: [(x: 0.0, y: 0.0), (x: 0.0, y: 300.0)],
+//!         edges: [(from: 0, to: 1)],
+//!     ),
+//!     lanes: [
+//!         (points: [(x: -400.0, y: -600.0), (x: 400.0, y: -600.0), (x: 0.0, y: -1200.0)], cars: 2),
+//!     ],
+//!     tiles: (
+//!         origin: (-2, 5),
+//!         rows: [
+//!             "..__##",
+//!             "..__##",
+//!         ],
+//!     ),
+//! )
+//! ```
+
+use crate::math::{Point, PolygonError, Rect, validate_polygon};
+use crate::roads::RoadGraph;
+use crate::tilemap::Tilemap;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Level {
+    #[serde(default)]
+    pub name: String,
+    pub spawn: Spawn,
+    #[serde(default)]
+    pub walls: Vec<WallDefinition>,
+    #[serde(default)]
+    pub regions: Vec<Region>,
+    /// See `roads.rs`.
+    #[serde(default)]
+    pub roads: RoadGraph,
+    #[serde(default)]
+    pub lanes: Vec<Lane>,
+    /// See `tilemap.rs`.
+    #[serde(default)]
+    pub tiles: TileRows,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spawn {
+    pub position: Point,
+    /// In degrees, clockwise. 0 is facing up.
+    #[serde(default)]
+    pub heading: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallDefinition {
+    /// Outline of the wall in world space. Can be concave, but edges can't
+    /// cross each other.
+    pub points: Vec<Point>,
+    /// In degrees, clockwise around the middle of the wall.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+/// A named area of the level, for things like pickup spots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    pub area: Rect,
+    #[serde(default)]
+    pub kind: RegionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RegionKind {
+    /// Just a named area, doesn't do anything by itself.
+    #[default]
+    Area,
+    /// Passengers wait here, and ask to be dropped off at another one.
+    Pickup,
+    /// Stopping here repairs the taxi, for a price.
+    Garage,
+}
+
+/// A loop that traffic drives around, see `traffic.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lane {
+    /// Waypoints in the order they're driven. The last one leads back to the
+    /// first. Traffic doesn't avoid walls, so keep the lane clear of them
+    /// (and the corners gentle, cars can't turn any tighter than the taxi).
+    pub points: Vec<Point>,
+    /// How many cars start on this lane, spread out between the waypoints.
+    #[serde(default)]
+    pub cars: usize,
+}
+
+/// Tiles written out as text, one character per tile (see `Tile::from_char`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TileRows {
+    /// Tile coordinates of the first row's first character.
+    #[serde(default)]
+    pub origin: (i32, i32),
+    #[serde(default)]
+    pub rows: Vec<String>,
+}
+
+impl TileRows {
+    pub fn to_tilemap(&self) -> Result<Tilemap, (usize, usize)> {
+        Tilemap::from_rows(self.origin, &self.rows)
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    /// `index` is the position in the level's `walls` list.
+    InvalidWall {
+        index: usize,
+        error: PolygonError,
+    },
+    InvalidRegion {
+        name: String,
+        reason: &'static str,
+    },
+    /// `index` is the position in the roads' `edges` list.
+    InvalidRoad {
+        index: usize,
+        reason: &'static str,
+    },
+    /// `index` is the position in the level's `lanes` list.
+    InvalidLane {
+        index: usize,
+        reason: &'static str,
+    },
+    /// A character in `tiles` that isn't a tile. Both start at 0.
+    InvalidTile {
+        row: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelError::Io(error) => write!(f, "couldn't read level: {}", error),
+            LevelError::Parse(error) => write!(f, "couldn't parse level: {}", error),
+            LevelError::InvalidWall { index, error } => write!(f, "wall {} {}", index, error),
+            LevelError::InvalidRegion { name, reason } => {
+                write!(f, "region \"{}\" {}", name, reason)
+            }
+            LevelError::InvalidRoad { index, reason } => write!(f, "road {} {}", index, reason),
+            LevelError::InvalidLane { index, reason } => write!(f, "lane {} {}", index, reason),
+            LevelError::InvalidTile { row, column } => {
+                write!(f, "unknown tile at row {}, column {}", row, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(error: std::io::Error) -> Self {
+        LevelError::Io(error)
+    }
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    /// Parses and validates a level from RON text.
+    pub fn parse(text: &str) -> Result<Self, LevelError> {
+        let level: Level = ron::from_str(text).map_err(LevelError::Parse)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    pub fn validate(&self) -> Result<(), LevelError> {
+        for (index, wall) in self.walls.iter().enumerate() {
+            validate_polygon(&wall.points)
+                .map_err(|error| LevelError::InvalidWall { index, error })?;
+        }
+
+        for (index, region) in self.regions.iter().enumerate() {
+            let invalid = |reason| LevelError::InvalidRegion {
+                name: region.name.clone(),
+                reason,
+            };
+
+            if region.area.w <= 0.0 || region.area.h <= 0.0 {
+                return Err(invalid("needs a positive width and height"));
+            }
+            if self.regions[..index]
+                .iter()
+                .any(|other| other.name == region.name)
+            {
+                return Err(invalid("is defined more than once"));
+            }
+        }
+
+        for index in 0..self.roads.edges.len() {
+            self.roads
+                .validate_edge(index)
+                .map_err(|reason| LevelError::InvalidRoad { index, reason })?;
+        }
+
+        for (index, lane) in self.lanes.iter().enumerate() {
+            let invalid = |reason| LevelError::InvalidLane { index, reason };
+
+            if lane.points.len() < 2 {
+                return Err(invalid("needs at least 2 points"));
+            }
+            // Cars start on a waypoint each.
+            if lane.cars > lane.points.len() {
+                return Err(invalid("has more cars than points"));
+            }
+        }
+
+        self.tiles
+            .to_tilemap()
+            .map_err(|(row, column)| LevelError::InvalidTile { row, column })?;
+
+        Ok(())
+    }
+}
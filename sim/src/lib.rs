@@ -0,0 +1,24 @@
+//! Everything needed to run the game without a window: the taxi, level
+//! geometry and the simulation step. Rendering and reading the keyboard live
+//! in the `game` binary.
+
+pub mod citygen;
+pub mod constants;
+pub mod events;
+pub mod gamestate;
+pub mod input;
+pub mod level;
+pub mod math;
+pub mod passenger;
+pub mod player;
+pub mod quadtree;
+pub mod replay;
+pub mod rng;
+pub mod roads;
+pub mod scoring;
+pub mod stats;
+pub mod tilemap;
+pub mod timestep;
+pub mod traffic;
+pub mod tuning;
+pub mod wall;
@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+
+    pub fn dot(&self, other: &Point) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns a unit vector in the same direction, or (0, 0) if the
+    /// length is 0.
+    pub fn normalized(&self) -> Point {
+        let length = self.length();
+        if length == 0.0 {
+            return Point::new(0.0, 0.0);
+        }
+        Point::new(self.x / length, self.y / length)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, scalar: f32) -> Point {
+        Point::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Axis aligned rectangle. `x`/`y` is the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Rect { x, y, w, h }
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.w / 2.0, self.y + self.h / 2.0)
+    }
+
+    /// Touching edges count as overlapping.
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        self.x <= other.x + other.w
+            && other.x <= self.x + self.w
+            && self.y <= other.y + other.h
+            && other.y <= self.y + self.h
+    }
+
+    pub fn contains_point(&self, point: &Point) -> bool {
+        point.x >= self.x
+            && point.y >= self.y
+            && point.x <= self.x + self.w
+            && point.y <= self.y + self.h
+    }
+
+    /// True if `other` is completely inside of this rect.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.w <= self.x + self.w
+            && other.y + other.h <= self.y + self.h
+    }
+}
+
+pub fn rotate_around_point(vertex: &mut Point, center_point: &Point, angle_radians: f32) {
+    let x_origin: f32 = vertex.x - center_point.x;
+    let y_origin: f32 = vertex.y - center_point.y;
+
+    let x_rotated: f32 = x_origin * angle_radians.cos() - y_origin * angle_radians.sin();
+    let y_rotated: f32 = x_origin * angle_radians.sin() + y_origin * angle_radians.cos();
+
+    vertex.x = x_rotated + center_point.x;
+    vertex.y = y_rotated + center_point.y;
+}
+
+pub fn convert_world_pos_to_camera_pos(world_pos: &Point, camera: &Rect) -> Point {
+    Point::new(world_pos.x - camera.x, world_pos.y - camera.y)
+}
+
+/// Inverse of `convert_world_pos_to_camera_pos`, e.g. for working out where
+/// a mouse click landed in the world.
+pub fn convert_camera_pos_to_world_pos(camera_pos: &Point, camera: &Rect) -> Point {
+    Point::new(camera_pos.x + camera.x, camera_pos.y + camera.y)
+}
+
+/// Axis aligned bounding box around the vertices.
+pub fn polygon_bounds(polygon: &[Point]) -> Rect {
+    let mut min = Point::new(f32::MAX, f32::MAX);
+    let mut max = Point::new(f32::MIN, f32::MIN);
+    for vertex in polygon {
+        min.x = min.x.min(vertex.x);
+        min.y = min.y.min(vertex.y);
+        max.x = max.x.max(vertex.x);
+        max.y = max.y.max(vertex.y);
+    }
+    Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+/// Result of two convex polygons overlapping.
+#[derive(Debug, Clone, Copy)]
+pub struct Collision {
+    /// How far the polygons are overlapping along `normal`.
+    pub overlap: f32,
+
+    /// Minimum translation vector; moving polygon `a` by this separates it
+    /// from polygon `b`.
+    pub mtv: Point,
+
+    /// Unit vector pointing from `b` towards `a` along the axis of least
+    /// overlap.
+    pub normal: Point,
+}
+
+/// Separating Axis Theorem test between two convex polygons.
+/// Vertices can be in either winding order.
+///
+/// Returns None if the polygons don't overlap.
+pub fn polygon_collision(a: &[Point], b: &[Point]) -> Option<Collision> {
+    let mut overlap = f32::MAX;
+    let mut normal = Point::new(0.0, 0.0);
+
+    for polygon in [a, b] {
+        for i in 0..polygon.len() {
+            let current = polygon[i];
+            let next = polygon[(i + 1) % polygon.len()]; // Wrap around to first point
+
+            // Perpendicular of the edge is the axis we project onto.
+            let edge = next - current;
+            let axis = Point::new(-edge.y, edge.x).normalized();
+            if axis.length() == 0.0 {
+                continue; // duplicate vertices
+            }
+
+            let (a_min, a_max) = project_polygon(a, &axis);
+            let (b_min, b_max) = project_polygon(b, &axis);
+
+            // Found a gap, so there can't be a collision.
+            if a_max < b_min || b_max < a_min {
+                return None;
+            }
+
+            let axis_overlap = (a_max - b_min).min(b_max - a_min);
+            if axis_overlap < overlap {
+                overlap = axis_overlap;
+                normal = axis;
+            }
+        }
+    }
+
+    // Make sure the normal pushes `a` away from `b`, not further into it.
+    let direction = polygon_centroid(a) - polygon_centroid(b);
+    if direction.dot(&normal) < 0.0 {
+        normal = -normal;
+    }
+
+    Some(Collision {
+        overlap,
+        mtv: normal * overlap,
+        normal,
+    })
+}
+
+fn project_polygon(polygon: &[Point], axis: &Point) -> (f32, f32) {
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+    for vertex in polygon {
+        let projection = vertex.dot(axis);
+        min = min.min(projection);
+        max = max.max(projection);
+    }
+    (min, max)
+}
+
+/// Average of the vertices; good enough for deciding which way to push.
+pub fn polygon_centroid(polygon: &[Point]) -> Point {
+    let mut sum = Point::new(0.0, 0.0);
+    for vertex in polygon {
+        sum = sum + *vertex;
+    }
+    sum * (1.0 / polygon.len() as f32)
+}
+
+/// Positive when the vertices go clockwise on screen (y points down).
+pub fn polygon_signed_area(polygon: &[Point]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        let current = polygon[i];
+        let next = polygon[(i + 1) % polygon.len()];
+        area += current.x * next.y - next.x * current.y;
+    }
+    area / 2.0
+}
+
+pub fn is_polygon_convex(polygon: &[Point]) -> bool {
+    let mut sign = 0.0;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let c = polygon[(i + 2) % polygon.len()];
+
+        let turn = cross(&(b - a), &(c - b));
+        if turn == 0.0 {
+            continue; // collinear
+        }
+        if sign == 0.0 {
+            sign = turn.signum();
+        } else if turn.signum() != sign {
+            return false;
+        }
+    }
+    true
+}
+
+/// Z component of the 3D cross product.
+fn cross(a: &Point, b: &Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// True if segment a1-a2 crosses segment b1-b2 (touching at the ends
+/// doesn't count).
+fn segments_cross(a1: &Point, a2: &Point, b1: &Point, b2: &Point) -> bool {
+    let d1 = cross(&(*a2 - *a1), &(*b1 - *a1));
+    let d2 = cross(&(*a2 - *a1), &(*b2 - *a1));
+    let d3 = cross(&(*b2 - *b1), &(*a1 - *b1));
+    let d4 = cross(&(*b2 - *b1), &(*a2 - *b1));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+/// Point on the segment from `start` to `end` closest to `point`; one of the
+/// ends if `point` is past it (or the segment has no length).
+pub fn closest_point_on_segment(point: &Point, start: &Point, end: &Point) -> Point {
+    let segment = *end - *start;
+    let length_squared = segment.dot(&segment);
+    if length_squared == 0.0 {
+        return *start;
+    }
+    let t = ((*point - *start).dot(&segment) / length_squared).clamp(0.0, 1.0);
+    *start + segment * t
+}
+
+/// Shortest distance between the outlines of two polygons, 0.0 if they
+/// touch or cross. Either can be concave. A polygon entirely inside the
+/// other isn't noticed, use `polygon_collision` for that.
+pub fn polygon_distance(a: &[Point], b: &[Point]) -> f32 {
+    let point_to_edge = |point: &Point, start: &Point, end: &Point| {
+        (closest_point_on_segment(point, start, end) - *point).length()
+    };
+
+    let mut distance = f32::MAX;
+    for i in 0..a.len() {
+        let a1 = &a[i];
+        let a2 = &a[(i + 1) % a.len()];
+        for j in 0..b.len() {
+            let b1 = &b[j];
+            let b2 = &b[(j + 1) % b.len()];
+            if segments_cross(a1, a2, b1, b2) {
+                return 0.0;
+            }
+            // Edges that don't cross are closest at one of their ends.
+            distance = distance
+                .min(point_to_edge(a1, b1, b2))
+                .min(point_to_edge(a2, b1, b2))
+                .min(point_to_edge(b1, a1, a2))
+                .min(point_to_edge(b2, a1, a2));
+        }
+    }
+    distance
+}
+
+/// Why a polygon can't be used as level geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolygonError {
+    TooFewPoints(usize),
+    NotFinite { index: usize },
+    DuplicatePoint { index: usize },
+    ZeroArea,
+    SelfIntersecting { edge_a: usize, edge_b: usize },
+}
+
+impl std::fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PolygonError::TooFewPoints(count) => {
+                write!(f, "needs at least 3 points, has {}", count)
+            }
+            PolygonError::NotFinite { index } => write!(f, "point {} isn't a finite number", index),
+            PolygonError::DuplicatePoint { index } => {
+                write!(f, "point {} is the same as the point before it", index)
+            }
+            PolygonError::ZeroArea => write!(f, "has no area (all points are in a line)"),
+            PolygonError::SelfIntersecting { edge_a, edge_b } => {
+                write!(f, "edges {} and {} cross each other", edge_a, edge_b)
+            }
+        }
+    }
+}
+
+/// Checks a polygon is "simple": enough points, no crossing edges, has an
+/// area. It can be concave.
+pub fn validate_polygon(polygon: &[Point]) -> Result<(), PolygonError> {
+    if polygon.len() < 3 {
+        return Err(PolygonError::TooFewPoints(polygon.len()));
+    }
+
+    for (index, point) in polygon.iter().enumerate() {
+        if !point.x.is_finite() || !point.y.is_finite() {
+            return Err(PolygonError::NotFinite { index });
+        }
+        let previous = polygon[(index + polygon.len() - 1) % polygon.len()];
+        if previous.x == point.x && previous.y == point.y {
+            return Err(PolygonError::DuplicatePoint { index });
+        }
+    }
+
+    let len = polygon.len();
+    for edge_a in 0..len {
+        for edge_b in (edge_a + 1)..len {
+            let a1 = &polygon[edge_a];
+            let a2 = &polygon[(edge_a + 1) % len];
+            let b1 = &polygon[edge_b];
+            let b2 = &polygon[(edge_b + 1) % len];
+            if segments_cross(a1, a2, b1, b2) {
+                return Err(PolygonError::SelfIntersecting { edge_a, edge_b });
+            }
+        }
+    }
+
+    if polygon_signed_area(polygon).abs() < f32::EPSILON {
+        return Err(PolygonError::ZeroArea);
+    }
+
+    Ok(())
+}
+
+/// Splits a simple polygon into convex pieces, since SAT only works on
+/// convex shapes. Convex polygons come back as-is, concave ones are
+/// triangulated by ear clipping.
+pub fn convex_decomposition(polygon: &[Point]) -> Vec<Vec<Point>> {
+    if is_polygon_convex(polygon) {
+        return vec![polygon.to_vec()];
+    }
+
+    // Ear clipping below assumes clockwise (on screen) winding.
+    let mut remaining: Vec<Point> = polygon.to_vec();
+    if polygon_signed_area(&remaining) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let len = remaining.len();
+        let ear = (0..len).find(|&i| {
+            let previous = remaining[(i + len - 1) % len];
+            let current = remaining[i];
+            let next = remaining[(i + 1) % len];
+
+            // Reflex corners can't be ears.
+            if cross(&(current - previous), &(next - current)) <= 0.0 {
+                return false;
+            }
+
+            // No other vertex can be inside the triangle.
+            remaining.iter().enumerate().all(|(j, point)| {
+                j == i
+                    || j == (i + len - 1) % len
+                    || j == (i + 1) % len
+                    || !point_in_triangle(point, &previous, &current, &next)
+            })
+        });
+
+        // Only happens with degenerate input; whatever is left is as good
+        // as we'll get.
+        let Some(ear) = ear else {
+            break;
+        };
+
+        triangles.push(vec![
+            remaining[(ear + len - 1) % len],
+            remaining[ear],
+            remaining[(ear + 1) % len],
+        ]);
+        remaining.remove(ear);
+    }
+    triangles.push(remaining);
+
+    triangles
+}
+
+fn point_in_triangle(point: &Point, a: &Point, b: &Point, c: &Point) -> bool {
+    let d1 = cross(&(*b - *a), &(*point - *a));
+    let d2 = cross(&(*c - *b), &(*point - *b));
+    let d3 = cross(&(*a - *c), &(*point - *c));
+    d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0
+}
@@ -0,0 +1,182 @@
+use crate::constants::*;
+use crate::events::GameEvent;
+use crate::gamestate::GameState;
+use crate::level::RegionKind;
+
+pub struct Passenger {
+    /// Name of the pickup region they're waiting at.
+    pub pickup: String,
+    /// Name of the pickup region they want to go to.
+    pub destination: String,
+    pub state: PassengerState,
+    pub spawned_at: f64,
+    /// Health the taxi lost during the ride.
+    pub damage: f32,
+}
+
+impl Passenger {
+    /// Seconds since being picked up, None if still waiting.
+    pub fn ride_time(&self, now: f64) -> Option<f64> {
+        match self.state {
+            PassengerState::Waiting => None,
+            PassengerState::Riding { picked_up_at } => Some(now - picked_up_at),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PassengerState {
+    Waiting,
+    Riding { picked_up_at: f64 },
+}
+
+/// A finished ride.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fare {
+    pub pickup: String,
+    pub destination: String,
+    pub ride_time: f64,
+    pub damage: f32,
+    pub amount: f32,
+    pub paid_at: f64,
+}
+
+/// How much a ride pays. `distance` is between the middle of the pickup and
+/// destination regions.
+pub fn calculate_fare(distance: f32, ride_time: f64, damage: f32) -> f32 {
+    let par_time = (distance / FARE_EXPECTED_SPEED) as f64 + FARE_GRACE_TIME;
+
+    let mut amount = FARE_BASE + distance * FARE_PER_DISTANCE;
+    if ride_time > par_time {
+        amount *= (par_time / ride_time) as f32;
+    }
+    amount -= damage * FARE_DAMAGE_PENALTY;
+
+    amount.max(0.0)
+}
+
+/// Spawns passengers, and picks up/drops off when the taxi is stopped in the
+/// right place.
+pub fn simulate_passengers(game_state: &mut GameState) {
+    spawn_passengers(game_state);
+
+    if game_state.player.speed() >= PASSENGER_PICKUP_MAX_SPEED {
+        return;
+    }
+
+    let taxi_center = game_state.player.center;
+    let is_taxi_in = |game_state: &GameState, region_name: &str| {
+        game_state
+            .region(region_name)
+            .is_some_and(|region| region.area.contains_point(&taxi_center))
+    };
+
+    // Only one passenger fits in the taxi, so drop off before picking up.
+    if let Some(index) = game_state.riding_passenger_index() {
+        if is_taxi_in(game_state, &game_state.passengers[index].destination) {
+            drop_off(game_state, index);
+        }
+        return;
+    }
+
+    let waiting_here = game_state.passengers.iter().position(|passenger| {
+        passenger.state == PassengerState::Waiting && is_taxi_in(game_state, &passenger.pickup)
+    });
+    if let Some(index) = waiting_here {
+        let passenger = &mut game_state.passengers[index];
+        passenger.state = PassengerState::Riding {
+            picked_up_at: game_state.time,
+        };
+        game_state.events.push(GameEvent::PassengerPickedUp {
+            pickup: passenger.pickup.clone(),
+            destination: passenger.destination.clone(),
+        });
+    }
+}
+
+fn spawn_passengers(game_state: &mut GameState) {
+    if game_state.time < game_state.next_passenger_at {
+        return;
+    }
+    game_state.next_passenger_at = game_state.time + PASSENGER_SPAWN_INTERVAL;
+
+    let waiting_count = game_state
+        .passengers
+        .iter()
+        .filter(|passenger| passenger.state == PassengerState::Waiting)
+        .count();
+    if waiting_count >= MAX_WAITING_PASSENGERS {
+        return;
+    }
+
+    let pickups: Vec<&str> = game_state
+        .regions
+        .iter()
+        .filter(|region| region.kind == RegionKind::Pickup)
+        .map(|region| region.name.as_str())
+        .collect();
+
+    // Somewhere nobody is already waiting at.
+    let free_pickups: Vec<&str> = pickups
+        .iter()
+        .copied()
+        .filter(|name| {
+            !game_state.passengers.iter().any(|passenger| {
+                passenger.state == PassengerState::Waiting && passenger.pickup == *name
+            })
+        })
+        .collect();
+
+    // Need somewhere to go as well as somewhere to wait.
+    if free_pickups.is_empty() || pickups.len() < 2 {
+        return;
+    }
+
+    let pickup = free_pickups[game_state.rng.index(free_pickups.len())].to_owned();
+    let destinations: Vec<&str> = pickups
+        .iter()
+        .copied()
+        .filter(|name| *name != pickup)
+        .collect();
+    let destination = destinations[game_state.rng.index(destinations.len())].to_owned();
+
+    game_state.passengers.push(Passenger {
+        pickup,
+        destination,
+        state: PassengerState::Waiting,
+        spawned_at: game_state.time,
+        damage: 0.0,
+    });
+}
+
+fn drop_off(game_state: &mut GameState, index: usize) {
+    let passenger = game_state.passengers.remove(index);
+    let PassengerState::Riding { picked_up_at } = passenger.state else {
+        return;
+    };
+
+    let (Some(pickup), Some(destination)) = (
+        game_state.region(&passenger.pickup),
+        game_state.region(&passenger.destination),
+    ) else {
+        return;
+    };
+    let distance = (destination.area.center() - pickup.area.center()).length();
+
+    let ride_time = game_state.time - picked_up_at;
+    let amount = calculate_fare(distance, ride_time, passenger.damage);
+
+    let fare = Fare {
+        pickup: passenger.pickup,
+        destination: passenger.destination,
+        ride_time,
+        damage: passenger.damage,
+        amount,
+        paid_at: game_state.time,
+    };
+    game_state.money += amount;
+    game_state
+        .events
+        .push(GameEvent::PassengerDroppedOff(fare.clone()));
+    game_state.fares.push(fare);
+}
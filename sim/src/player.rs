@@ -0,0 +1,834 @@
+use crate::constants::{
+    DAMAGE_MIN_IMPACT_SPEED, DAMAGE_PER_IMPACT_SPEED, MAX_HEALTH, PlayerShapeConfig,
+};
+use crate::events::GameEvent;
+use crate::math::Collision;
+use crate::math::Point;
+use crate::math::Rect;
+use crate::math::polygon_bounds;
+use crate::math::rotate_around_point;
+use crate::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+/// Aka the taxi
+pub struct Player {
+    /// This is just a rectangle, but its treated as a polygon.
+    /// This'll make collision detection easier later.
+    pub points: [Point; 8],
+
+    /// Center position of the Player (which is a rect)
+    pub center: Point,
+
+    /// `points` and `center` from the end of the last simulation tick, so the
+    /// renderer can interpolate between ticks.
+    pub previous_points: [Point; 8],
+    pub previous_center: Point,
+
+    /// In radians.
+    rotation: f32,
+
+    /// Normal vector pointed in the direction the player is facing.
+    pub forward_normal: Point,
+
+    /// World space velocity. Each tick this gets split into the part along
+    /// `forward_normal` (what gas/brakes/drag act on) and the sideways part
+    /// (what the tires grip against).
+    pub velocity: Point,
+
+    /// Sideways velocity was more than the tires could hold, so the car is
+    /// sliding instead of going where it points.
+    pub drifting: bool,
+
+    pub shift_mode: ShiftMode,
+
+    pub is_gas_held: bool,
+
+    pub is_brake_held: bool,
+
+    /// Seconds the brake has been held for; brakes bite harder the longer
+    /// they're held.
+    pub brake_held_time: f32,
+
+    pub ticks_since_switching_into_drive: f64,
+
+    pub ticks_since_gas_was_activated: f64,
+
+    pub ticks_to_curr_crazy_dash_end: f64,
+
+    /// Seconds between shifting into drive and hitting the gas, as of this
+    /// tick. Decides the dash timing, and scoring uses it to tell how good a
+    /// perfect dash was.
+    pub time_between_drive_and_gas: f64,
+
+    pub is_crazy_dashing: bool,
+
+    /// 0.0 to 1.0, used up by dashing and refilled over time.
+    pub boost: f32,
+
+    /// Dashes in a row, counting the current/last one. Perfectly timed
+    /// dashes soon enough after the last one add to it, anything else starts
+    /// over at 1. 0 once the chain window runs out.
+    pub crazy_dash_chain: u32,
+
+    /// How much harder than a normal dash the current one pushes, from the
+    /// chain.
+    pub crazy_dash_strength: f32,
+
+    /// When the last dash ended, for the cooldown and chaining.
+    pub last_crazy_dash_end: Option<f64>,
+
+    /// Dashes, gear changes etc. since these were last taken, see
+    /// `GameState::events`.
+    pub events: Vec<GameEvent>,
+
+    pub drag: f32,
+
+    /// `MAX_HEALTH` down to 0.0, lost in crashes. At 0.0 the car's wrecked.
+    pub health: f32,
+
+    /// Swapped out live when the tuning file changes, see `set_tuning`.
+    pub tuning: Tuning,
+}
+
+/// Gear the taxi is in.
+///
+/// DRIVE <-> NEUTRAL <-> REVERSE can be shifted between freely, except that
+/// reverse only engages below `REVERSE_ENGAGE_MAX_SPEED`; asking for reverse
+/// while driving forward faster than that puts the taxi in NEUTRAL until it
+/// slows down. PARK only engages when stopped and holds the taxi in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ShiftMode {
+    DRIVE,
+    REVERSE,
+    NEUTRAL,
+    PARK,
+}
+
+impl Player {
+    pub fn new(x: f32, y: f32) -> Self {
+        let tuning = Tuning::default();
+        let center = Point::new(x, y);
+        let points = create_player_vertices(&center, &tuning.player_shape);
+        let forward_normal = Point::new(0.0, -1.0);
+        let velocity = Point::new(0.0, 0.0);
+
+        Player {
+            points,
+            center,
+            previous_points: points,
+            previous_center: center,
+            forward_normal,
+            rotation: 0.0,
+            velocity,
+            drifting: false,
+            shift_mode: ShiftMode::DRIVE,
+            is_gas_held: false,
+            is_brake_held: false,
+            brake_held_time: 0.0,
+            ticks_since_switching_into_drive: 0.0,
+            ticks_since_gas_was_activated: 0.0,
+            ticks_to_curr_crazy_dash_end: 0.0,
+            time_between_drive_and_gas: 0.0,
+            is_crazy_dashing: false,
+            boost: 1.0,
+            crazy_dash_chain: 0,
+            crazy_dash_strength: 1.0,
+            last_crazy_dash_end: None,
+            events: Vec::new(),
+            drag: tuning.car_default_drag,
+            health: MAX_HEALTH,
+            tuning,
+        }
+    }
+
+    /// Does what the action asks, for this tick. Held actions need to be
+    /// applied every tick they're held.
+    pub fn apply_action(&mut self, action: &PlayerAction, time: f64, delta_time: f32) {
+        match action {
+            PlayerAction::GasHeld => {
+                self.is_gas_held = true;
+            }
+            PlayerAction::GasActivated => {
+                self.ticks_since_gas_was_activated = time;
+            }
+            PlayerAction::ShiftIntoDrive => self.shift_into_drive(time),
+            PlayerAction::ShiftIntoReverse => self.shift_into_reverse(),
+            PlayerAction::ShiftIntoNeutral => self.shift_into_neutral(),
+            PlayerAction::ShiftIntoPark => self.shift_into_park(),
+            PlayerAction::Brake => {
+                self.is_brake_held = true;
+            }
+            PlayerAction::Steer(steer) => self.rotate(*steer, delta_time),
+            PlayerAction::Reposition { x, y, reset_motion } => {
+                self.reposition(*x, *y, *reset_motion)
+            }
+        }
+    }
+
+    fn is_player_moving(&self) -> bool {
+        self.velocity.length() > 0.0
+    }
+
+    /// `steer` is how hard to turn, from -1.0 (full left) to 1.0 (full
+    /// right).
+    pub fn rotate(&mut self, steer: f32, delta_time: f32) {
+        if !self.is_player_moving() {
+            return;
+        }
+
+        // Allow sharper turn if we're moving faster.
+        // So, multiply the rotation speed with velocity in some way.
+        // Turning only rotates the car, not its velocity; the tires pull the
+        // velocity around to match in `simulate` (or don't, and we drift).
+        let turn_velocity_modifier = self.forward_speed() / 100.0;
+
+        let rotation_degrees = self.tuning.player_rotation_speed
+            * turn_velocity_modifier
+            * steer.clamp(-1.0, 1.0)
+            * delta_time;
+        let rotation_radians = rotation_degrees * PI / 180.0;
+        self.rotation += rotation_radians;
+
+        // Update forward normal using total rotation, and update the vertices
+        // with the rotation of this frame only.
+        self.forward_normal.x = self.rotation.sin();
+        self.forward_normal.y = -(self.rotation.cos());
+        for vertex in self.points.iter_mut() {
+            rotate_around_point(vertex, &self.center, rotation_radians);
+        }
+    }
+
+    pub fn apply_gas(&mut self, delta_time: f32) {
+        if self.is_wrecked() {
+            return;
+        }
+        // Engine isn't connected to the wheels.
+        if matches!(self.shift_mode, ShiftMode::NEUTRAL | ShiftMode::PARK) {
+            return;
+        }
+
+        let forward_speed = self.forward_speed();
+
+        // If mid crazy dash, go way past the normal max velocity.
+        if self.is_crazy_dashing {
+            let strength = self.crazy_dash_strength;
+            if forward_speed < self.tuning.crazy_dash_max_velocity * strength {
+                self.set_forward_speed(
+                    forward_speed + self.tuning.crazy_dash_velocity * strength * delta_time,
+                );
+            }
+            return;
+        }
+        match self.shift_mode {
+            ShiftMode::DRIVE => {
+                if forward_speed < self.tuning.player_max_velocity {
+                    self.set_forward_speed(forward_speed + self.tuning.gas_velocity * delta_time);
+                }
+            }
+            ShiftMode::REVERSE => {
+                if forward_speed > self.tuning.player_max_reverse_velocity {
+                    self.set_forward_speed(
+                        forward_speed - self.tuning.reverse_velocity * delta_time,
+                    );
+                }
+            }
+            ShiftMode::NEUTRAL | ShiftMode::PARK => (),
+        }
+    }
+
+    /// Slows the car towards a stop (in either direction) without ever
+    /// pushing it the other way.
+    pub fn apply_brake(&mut self, delta_time: f32) {
+        // Brakes start soft and ramp up to full strength, so tapping the
+        // brake is a small correction and holding it is an emergency stop.
+        let ramp = (self.brake_held_time / self.tuning.brake_ramp_time).min(1.0);
+        let strength =
+            self.tuning.brake_initial_strength + (1.0 - self.tuning.brake_initial_strength) * ramp;
+        let deceleration = self.tuning.brake_deceleration * strength * delta_time;
+
+        // Brakes stop the wheels rolling; sliding sideways is up to the tires.
+        self.set_forward_speed(towards_zero(self.forward_speed(), deceleration));
+    }
+
+    /// Tires cancel out sideways velocity, up to how much grip they have.
+    /// Anything past that is a drift.
+    fn apply_grip(&mut self, delta_time: f32) {
+        let grip = if self.drifting {
+            self.tuning.tire_sliding_grip
+        } else {
+            self.tuning.tire_grip
+        };
+        let lateral_speed = self.lateral_speed();
+        let grip_this_tick = grip * delta_time;
+
+        if lateral_speed.abs() <= grip_this_tick {
+            // Tires hold; the car goes where it's pointed.
+            self.set_lateral_speed(0.0);
+            self.drifting = false;
+            return;
+        }
+
+        // Broke loose; sliding tires grip less, so the drift keeps going
+        // until the car slows or straightens out enough to catch it.
+        self.set_lateral_speed(towards_zero(lateral_speed, grip_this_tick));
+        self.drifting = true;
+    }
+
+    /// Call at the start of every simulation tick, before anything moves.
+    pub fn save_previous_position(&mut self) {
+        self.previous_points = self.points;
+        self.previous_center = self.center;
+    }
+
+    /// Position between the last tick and the current one.
+    /// `alpha` of 0.0 is the last tick, 1.0 is the current one.
+    pub fn interpolated_points(&self, alpha: f32) -> [Point; 8] {
+        let mut points = self.points;
+        for (point, previous) in points.iter_mut().zip(self.previous_points.iter()) {
+            *point = lerp_point(previous, point, alpha);
+        }
+        points
+    }
+
+    pub fn interpolated_center(&self, alpha: f32) -> Point {
+        lerp_point(&self.previous_center, &self.center, alpha)
+    }
+
+    /// `time` is the simulation clock (see `GameState::time`).
+    pub fn simulate(&mut self, delta_time: f32, time: f64) {
+        self.update_crazy_dash(time, delta_time);
+
+        //let mut drag = CAR_DEFAULT_DRAG;
+        if self.is_crazy_dashing {
+            self.drag = self.tuning.crazy_dash_drag;
+        }
+
+        // apply drag to car when velocity > 0
+        let forward_speed = self.forward_speed();
+        self.set_forward_speed(towards_zero(forward_speed, self.drag * delta_time));
+
+        // Begin increasing the drag if less than the default (mid crazy dash).
+        // (without this, the car very quickly slows down after a crazy dash)
+        if self.drag < self.tuning.car_default_drag {
+            let mut new_drag =
+                self.tuning.crazy_dash_drag_recovery / self.forward_speed() * delta_time; // TODO: put in function
+
+            if new_drag < 0.0 {
+                new_drag = 12.0 * delta_time;
+            }
+
+            self.drag += new_drag;
+            if self.drag > self.tuning.car_default_drag {
+                self.drag = self.tuning.car_default_drag;
+            }
+        }
+
+        if self.is_brake_held {
+            self.brake_held_time += delta_time;
+            self.apply_brake(delta_time);
+        } else {
+            self.brake_held_time = 0.0;
+        }
+
+        self.apply_grip(delta_time);
+
+        // Parked cars don't roll.
+        if self.shift_mode == ShiftMode::PARK {
+            self.velocity = Point::new(0.0, 0.0);
+            self.drifting = false;
+        }
+
+        // If velocity is near 0, just set to 0.
+        // (Prevents buggy behavior at lower speeds)
+        if self.forward_speed().abs() < 2.5 {
+            self.set_forward_speed(0.0);
+        }
+
+        // Apply velocity if gas is held (either drive or reverse)
+        if self.is_gas_held {
+            self.apply_gas(delta_time); // TODO: this could be named better.
+        }
+
+        // Apply the velocity to each of the verticies of the car.
+        self.translate(self.velocity.x * delta_time, self.velocity.y * delta_time);
+
+        // this will be set to true again before simulate is ran in the next frame.
+        self.is_gas_held = false;
+        self.is_brake_held = false;
+    }
+
+    pub fn shift_into_drive(&mut self, time: f64) {
+        // Neutral counts too, since that's where reverse ends up when the
+        // car is going too fast for it.
+        if matches!(self.shift_mode, ShiftMode::REVERSE | ShiftMode::NEUTRAL) {
+            // delimiter cut; mark the time of the switch for use to detect
+            // crazy dashes.
+            self.ticks_since_switching_into_drive = time;
+        }
+
+        self.set_shift_mode(ShiftMode::DRIVE);
+    }
+
+    /// Only engages if the car is (nearly) stopped or already going
+    /// backwards, otherwise the gearbox goes into neutral until it is.
+    pub fn shift_into_reverse(&mut self) {
+        if self.forward_speed() > self.tuning.reverse_engage_max_speed {
+            self.set_shift_mode(ShiftMode::NEUTRAL);
+            return;
+        }
+        self.set_shift_mode(ShiftMode::REVERSE);
+    }
+
+    pub fn shift_into_neutral(&mut self) {
+        self.set_shift_mode(ShiftMode::NEUTRAL);
+    }
+
+    /// Only engages when stopped.
+    pub fn shift_into_park(&mut self) {
+        if self.speed() <= self.tuning.park_engage_max_speed {
+            self.set_shift_mode(ShiftMode::PARK);
+        }
+    }
+
+    fn set_shift_mode(&mut self, shift_mode: ShiftMode) {
+        if shift_mode != self.shift_mode {
+            self.events.push(GameEvent::GearChanged {
+                from: self.shift_mode,
+                to: shift_mode,
+            });
+        }
+        self.shift_mode = shift_mode;
+    }
+
+    /// Teleports the taxi to world position `x`/`y`. Keeps going the same
+    /// way at the same speed, unless `reset_motion`, in which case it ends up
+    /// stopped and facing up like a fresh spawn.
+    pub fn reposition(&mut self, x: f32, y: f32, reset_motion: bool) {
+        self.center = Point::new(x, y);
+
+        if reset_motion {
+            self.rotation = 0.0;
+            self.forward_normal = Point::new(0.0, -1.0);
+            self.velocity = Point::new(0.0, 0.0);
+            self.drifting = false;
+            if self.is_crazy_dashing {
+                self.is_crazy_dashing = false;
+                self.ticks_to_curr_crazy_dash_end = -1.0;
+                self.events.push(GameEvent::DashEnded);
+            }
+            self.last_crazy_dash_end = None;
+            self.crazy_dash_chain = 0;
+            self.drag = self.tuning.car_default_drag;
+        }
+
+        // Don't interpolate across the teleport.
+        self.rebuild_points();
+    }
+
+    /// Takes effect straight away, including reshaping the car if
+    /// `player_shape` changed.
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        let shape_changed = tuning.player_shape != self.tuning.player_shape;
+        self.tuning = tuning;
+
+        if shape_changed {
+            self.rebuild_points();
+        }
+    }
+
+    /// Recreates `points` around `center` at the current rotation. Also
+    /// resets the interpolation, since the points jump.
+    fn rebuild_points(&mut self) {
+        self.points = create_player_vertices(&self.center, &self.tuning.player_shape);
+        for vertex in self.points.iter_mut() {
+            rotate_around_point(vertex, &self.center, self.rotation);
+        }
+        self.save_previous_position();
+    }
+
+    /// Turns the car to face `rotation` (radians, 0 is facing up) straight
+    /// away, e.g. for spawning.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        let rotation_radians = rotation - self.rotation;
+        self.rotation = rotation;
+
+        self.forward_normal.x = self.rotation.sin();
+        self.forward_normal.y = -(self.rotation.cos());
+        for vertex in self.points.iter_mut() {
+            rotate_around_point(vertex, &self.center, rotation_radians);
+        }
+        self.save_previous_position();
+    }
+
+    // Get a point some distance ahead for drawing debug vector
+    pub fn get_player_normal_vector_for_debug(&self, distance: f32) -> Point {
+        let forward_vec = &self.forward_normal;
+        Point::new(
+            self.center.x + forward_vec.x * distance,
+            self.center.y + forward_vec.y * distance,
+        )
+    }
+
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.center.x += x;
+        self.center.y += y;
+
+        for point in &mut self.points {
+            point.x += x;
+            point.y += y;
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        polygon_bounds(&self.points)
+    }
+
+    /// Push the car out of whatever it hit and bounce the velocity off of the
+    /// contact normal.
+    ///
+    /// Returns the impact speed (how fast the car was going into the wall).
+    pub fn resolve_collision(&mut self, collision: &Collision) -> f32 {
+        self.translate(collision.mtv.x, collision.mtv.y);
+
+        let velocity_along_normal = self.velocity.dot(&collision.normal);
+
+        // Already moving away from the wall; being pushed out is enough.
+        if velocity_along_normal >= 0.0 {
+            return 0.0;
+        }
+        let impact_speed = -velocity_along_normal;
+
+        // Head on hits bounce the car backwards, glancing hits slide it along
+        // the wall (and the tires sort out the sideways part next tick).
+        self.velocity = self.velocity
+            - collision.normal * ((1.0 + self.tuning.wall_restitution) * velocity_along_normal);
+        self.take_impact(impact_speed);
+
+        impact_speed
+    }
+
+    /// Like `resolve_collision`, but for two cars hitting each other. Both
+    /// get pushed apart and bounce off each other (they weigh the same).
+    /// `collision` is from `self`'s point of view.
+    ///
+    /// Returns the impact speed (how fast they were closing in on each other).
+    pub fn resolve_car_collision(&mut self, other: &mut Player, collision: &Collision) -> f32 {
+        let half_mtv = collision.mtv * 0.5;
+        self.translate(half_mtv.x, half_mtv.y);
+        other.translate(-half_mtv.x, -half_mtv.y);
+
+        let closing_velocity = (self.velocity - other.velocity).dot(&collision.normal);
+        if closing_velocity >= 0.0 {
+            return 0.0;
+        }
+
+        // Split evenly since they're the same weight. Cars are about as
+        // bouncy as walls.
+        let impulse =
+            collision.normal * (-(1.0 + self.tuning.wall_restitution) * closing_velocity / 2.0);
+        self.velocity = self.velocity + impulse;
+        other.velocity = other.velocity - impulse;
+
+        let impact_speed = -closing_velocity;
+        self.take_impact(impact_speed);
+        other.take_impact(impact_speed);
+        impact_speed
+    }
+
+    pub fn is_wrecked(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    /// Fixes up to `amount` health, returns how much was actually fixed.
+    pub fn repair(&mut self, amount: f32) -> f32 {
+        let repaired = amount.min(MAX_HEALTH - self.health).max(0.0);
+        self.health += repaired;
+        repaired
+    }
+
+    /// Health lost for hitting something at `impact_speed` (along the contact
+    /// normal, so scraping along a wall is much cheaper than a head on hit).
+    fn take_impact(&mut self, impact_speed: f32) {
+        let damage = (impact_speed - DAMAGE_MIN_IMPACT_SPEED).max(0.0) * DAMAGE_PER_IMPACT_SPEED;
+        let was_wrecked = self.is_wrecked();
+        self.health = (self.health - damage).max(0.0);
+        if self.is_wrecked() && !was_wrecked {
+            self.events.push(GameEvent::Wrecked);
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// Speed along the direction the car is facing; negative when reversing.
+    pub fn forward_speed(&self) -> f32 {
+        self.velocity.dot(&self.forward_normal)
+    }
+
+    /// Sideways speed; positive is sliding towards the car's right.
+    pub fn lateral_speed(&self) -> f32 {
+        self.velocity.dot(&self.right_normal())
+    }
+
+    /// Angle between where the car points and where it's actually going, in
+    /// radians. Positive when sliding right. 0.0 when not moving.
+    pub fn drift_angle(&self) -> f32 {
+        if !self.is_player_moving() {
+            return 0.0;
+        }
+        self.lateral_speed().atan2(self.forward_speed())
+    }
+
+    /// Normal vector pointed out the right side of the car.
+    fn right_normal(&self) -> Point {
+        Point::new(-self.forward_normal.y, self.forward_normal.x)
+    }
+
+    fn set_forward_speed(&mut self, speed: f32) {
+        self.velocity = self.forward_normal * speed + self.right_normal() * self.lateral_speed();
+    }
+
+    fn set_lateral_speed(&mut self, speed: f32) {
+        self.velocity = self.forward_normal * self.forward_speed() + self.right_normal() * speed;
+    }
+
+    /// Hash of everything that affects how the player moves, used by replays
+    /// to check that a run plays out exactly the same way (FNV-1a).
+    pub fn checksum(&self) -> u64 {
+        let mut values = vec![
+            self.center.x,
+            self.center.y,
+            self.rotation,
+            self.forward_normal.x,
+            self.forward_normal.y,
+            self.velocity.x,
+            self.velocity.y,
+            self.drag,
+            self.health,
+            self.boost,
+            self.crazy_dash_strength,
+        ];
+        for point in self.points.iter() {
+            values.push(point.x);
+            values.push(point.y);
+        }
+
+        let mut bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        bytes.push(self.shift_mode as u8);
+        bytes.push(self.is_crazy_dashing as u8);
+        bytes.push(self.drifting as u8);
+        bytes.extend_from_slice(&self.ticks_to_curr_crazy_dash_end.to_le_bytes());
+        bytes.extend_from_slice(&self.crazy_dash_chain.to_le_bytes());
+        if let Some(end) = self.last_crazy_dash_end {
+            bytes.extend_from_slice(&end.to_le_bytes());
+        }
+
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+
+    fn update_crazy_dash(&mut self, time: f64, delta_time: f32) {
+        if self.is_crazy_dashing && time > self.ticks_to_curr_crazy_dash_end {
+            self.end_crazy_dash(time);
+        }
+
+        if !self.is_crazy_dashing {
+            self.boost = (self.boost + self.tuning.crazy_dash_boost_regen * delta_time).min(1.0);
+
+            let chain_over = self
+                .last_crazy_dash_end
+                .is_some_and(|end| time > end + self.tuning.crazy_dash_chain_window);
+            if chain_over {
+                self.crazy_dash_chain = 0;
+            }
+        }
+
+        let time_between_drive_and_gas =
+            self.ticks_since_gas_was_activated - self.ticks_since_switching_into_drive;
+
+        self.time_between_drive_and_gas = time_between_drive_and_gas;
+
+        let Some(timing) = self.crazy_dash_timing(time_between_drive_and_gas) else {
+            return;
+        };
+        // Each press only gets one go, whether or not it turns into a dash.
+        self.ticks_since_gas_was_activated = -1.0;
+        self.ticks_since_switching_into_drive = -1.0;
+
+        if self.is_wrecked() {
+            return;
+        }
+        let cooling_down = self
+            .last_crazy_dash_end
+            .is_some_and(|end| time < end + self.tuning.crazy_dash_cooldown);
+        if self.is_crazy_dashing || cooling_down {
+            self.events
+                .push(GameEvent::DashDenied(DashDenied::Cooldown));
+            return;
+        }
+        if self.boost < self.tuning.crazy_dash_boost_cost {
+            self.events.push(GameEvent::DashDenied(DashDenied::NoBoost));
+            return;
+        }
+
+        let chained = timing == DashTiming::Perfect
+            && self
+                .last_crazy_dash_end
+                .is_some_and(|end| time <= end + self.tuning.crazy_dash_chain_window);
+        self.crazy_dash_chain = if chained {
+            (self.crazy_dash_chain + 1).min(self.tuning.crazy_dash_max_chain.max(1))
+        } else {
+            1
+        };
+        self.crazy_dash_strength =
+            1.0 + self.tuning.crazy_dash_chain_bonus * (self.crazy_dash_chain - 1) as f32;
+
+        self.boost -= self.tuning.crazy_dash_boost_cost;
+        self.is_crazy_dashing = true;
+        self.ticks_to_curr_crazy_dash_end = time + self.tuning.crazy_dash_length;
+        self.events.push(GameEvent::DashStarted {
+            timing,
+            chain: self.crazy_dash_chain,
+            strength: self.crazy_dash_strength,
+        });
+    }
+
+    /// How well a gas press `time_between_drive_and_gas` seconds after
+    /// shifting into drive was timed. None if it's not a dash at all.
+    fn crazy_dash_timing(&self, time_between_drive_and_gas: f64) -> Option<DashTiming> {
+        if self
+            .tuning
+            .crazy_dash_perfect_timing
+            .contains(&time_between_drive_and_gas)
+        {
+            Some(DashTiming::Perfect)
+        } else if self
+            .tuning
+            .crazy_dash_input_timing
+            .contains(&time_between_drive_and_gas)
+        {
+            Some(DashTiming::Good)
+        } else {
+            None
+        }
+    }
+
+    fn end_crazy_dash(&mut self, time: f64) {
+        self.is_crazy_dashing = false;
+        self.ticks_to_curr_crazy_dash_end = -1.0;
+        self.last_crazy_dash_end = Some(time);
+        self.events.push(GameEvent::DashEnded);
+    }
+}
+
+/// Moves `value` towards 0.0 by `amount` without going past it.
+fn towards_zero(value: f32, amount: f32) -> f32 {
+    if value.abs() <= amount {
+        0.0
+    } else {
+        value - amount * value.signum()
+    }
+}
+
+fn lerp_point(from: &Point, to: &Point, alpha: f32) -> Point {
+    Point::new(
+        from.x + (to.x - from.x) * alpha,
+        from.y + (to.y - from.y) * alpha,
+    )
+}
+
+fn create_player_vertices(center: &Point, config: &PlayerShapeConfig) -> [Point; 8] {
+    let vertices = [
+        // Top-left corner
+        (center.x - config.half_width, center.y - config.half_height),
+        // Inner top-left
+        (
+            center.x - config.half_width + config.inner_space_w,
+            center.y - config.half_height - config.inner_space_h,
+        ),
+        // Inner top-right
+        (
+            center.x + config.half_width - config.inner_space_w,
+            center.y - config.half_height - config.inner_space_h,
+        ),
+        // Top-right corner
+        (center.x + config.half_width, center.y - config.half_height),
+        // Bottom-right corner
+        (center.x + config.half_width, center.y + config.half_height),
+        // Inner bottom-right
+        (
+            center.x + config.half_width - config.inner_space_w,
+            center.y + config.half_height + config.inner_space_h,
+        ),
+        // Inner bottom-left
+        (
+            center.x - config.half_width + config.inner_space_w,
+            center.y + config.half_height + config.inner_space_h,
+        ),
+        // Bottom-left corner
+        (center.x - config.half_width, center.y + config.half_height),
+    ];
+
+    // Convert tuples to Points
+    vertices.map(|(x, y)| Point::new(x, y))
+}
+
+/// How close to the sweet spot a crazy dash was triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashTiming {
+    /// Inside `crazy_dash_perfect_timing`; can chain onto the last dash.
+    Perfect,
+    /// Inside `crazy_dash_input_timing` but not perfect; starts a new chain.
+    Good,
+}
+
+/// Why a well timed dash didn't happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashDenied {
+    /// Already dashing, or the last one only just ended.
+    Cooldown,
+    /// Not enough boost left.
+    NoBoost,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlayerAction {
+    GasHeld,
+    GasActivated,
+    ShiftIntoDrive,
+    ShiftIntoReverse,
+    ShiftIntoNeutral,
+    ShiftIntoPark,
+    Brake,
+    /// How hard to turn, -1.0 (full left) to 1.0 (full right). Keyboards
+    /// only ever do full lock, gamepad sticks can do anything in between.
+    Steer(f32),
+    /// Teleport to a world position, see `Player::reposition`.
+    Reposition {
+        x: f32,
+        y: f32,
+        reset_motion: bool,
+    },
+}
+
+impl PlayerAction {
+    /// Actions that come from a single key press/click rather than a key
+    /// being held. These should only be simulated once, even if a frame runs
+    /// multiple simulation ticks.
+    pub fn is_one_shot(&self) -> bool {
+        matches!(
+            self,
+            PlayerAction::GasActivated | PlayerAction::Reposition { .. }
+        )
+    }
+}
@@ -0,0 +1,155 @@
+use crate::math::Rect;
+
+/// Node splits into 4 once it's holding more than this many items.
+const MAX_ITEMS_PER_NODE: usize = 8;
+const MAX_DEPTH: usize = 8;
+
+/// Spatial index over axis aligned bounding boxes, used as a broadphase
+/// before doing the (more expensive) polygon collision checks.
+///
+/// Items that don't fit entirely into one child stay in the parent node,
+/// so there is never any duplication.
+pub struct Quadtree<T> {
+    root: QuadtreeNode<T>,
+    len: usize,
+}
+
+struct QuadtreeNode<T> {
+    bounds: Rect,
+    depth: usize,
+    items: Vec<(T, Rect)>,
+    children: Option<Box<[QuadtreeNode<T>; 4]>>,
+}
+
+impl<T: Copy + PartialEq> Quadtree<T> {
+    /// Items outside of `bounds` can still be inserted, they just won't
+    /// benefit from the tree.
+    pub fn new(bounds: Rect) -> Self {
+        Self {
+            root: QuadtreeNode::new(bounds, 0),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, item: T, bounds: Rect) {
+        self.root.insert(item, bounds);
+        self.len += 1;
+    }
+
+    /// `bounds` must be the same rect the item was inserted with.
+    /// Returns false if the item wasn't found.
+    pub fn remove(&mut self, item: T, bounds: &Rect) -> bool {
+        let removed = self.root.remove(item, bounds);
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Every item whose bounds overlap `area`.
+    pub fn query(&self, area: &Rect) -> Vec<T> {
+        let mut results = Vec::new();
+        self.root.query(area, &mut results);
+        results
+    }
+
+    pub fn clear(&mut self) {
+        self.root = QuadtreeNode::new(self.root.bounds, 0);
+        self.len = 0;
+    }
+}
+
+impl<T: Copy + PartialEq> QuadtreeNode<T> {
+    fn new(bounds: Rect, depth: usize) -> Self {
+        Self {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn insert(&mut self, item: T, bounds: Rect) {
+        if let Some(child) = self.child_containing(&bounds) {
+            child.insert(item, bounds);
+            return;
+        }
+
+        self.items.push((item, bounds));
+
+        if self.children.is_none()
+            && self.items.len() > MAX_ITEMS_PER_NODE
+            && self.depth < MAX_DEPTH
+        {
+            self.split();
+        }
+    }
+
+    fn remove(&mut self, item: T, bounds: &Rect) -> bool {
+        if let Some(child) = self.child_containing(bounds) {
+            return child.remove(item, bounds);
+        }
+
+        match self.items.iter().position(|(other, _)| *other == item) {
+            Some(index) => {
+                self.items.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query(&self, area: &Rect, results: &mut Vec<T>) {
+        if !self.bounds.overlaps(area) && self.depth > 0 {
+            return;
+        }
+
+        for (item, bounds) in self.items.iter() {
+            if bounds.overlaps(area) {
+                results.push(*item);
+            }
+        }
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query(area, results);
+            }
+        }
+    }
+
+    fn split(&mut self) {
+        let half_w = self.bounds.w / 2.0;
+        let half_h = self.bounds.h / 2.0;
+        let x = self.bounds.x;
+        let y = self.bounds.y;
+        let depth = self.depth + 1;
+
+        self.children = Some(Box::new([
+            QuadtreeNode::new(Rect::new(x, y, half_w, half_h), depth),
+            QuadtreeNode::new(Rect::new(x + half_w, y, half_w, half_h), depth),
+            QuadtreeNode::new(Rect::new(x, y + half_h, half_w, half_h), depth),
+            QuadtreeNode::new(Rect::new(x + half_w, y + half_h, half_w, half_h), depth),
+        ]));
+
+        // Push down whatever fits into the new children.
+        let items = std::mem::take(&mut self.items);
+        for (item, bounds) in items {
+            self.insert(item, bounds);
+        }
+    }
+
+    fn child_containing(&mut self, bounds: &Rect) -> Option<&mut QuadtreeNode<T>> {
+        let children = self.children.as_mut()?;
+        children
+            .iter_mut()
+            .find(|child| child.bounds.contains_rect(bounds))
+    }
+}
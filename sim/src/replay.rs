@@ -0,0 +1,197 @@
+//! Recording and playing back inputs, to reproduce bugs exactly.
+//!
+//! Replay files are JSON lines: a header line, then one line per simulation
+//! tick. Every tick stores the game state checksum after it was simulated, so a
+//! replay that goes out of sync can point at the exact tick it happened on.
+
+use crate::citygen::generate_city;
+use crate::gamestate::{GameState, simulate};
+use crate::input::InputFrame;
+use crate::level::{Level, LevelError};
+use crate::math::Point;
+use crate::player::{Player, PlayerAction};
+use crate::tuning::Tuning;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    /// Where the player started.
+    pub spawn: Point,
+    /// Level file that was being played, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<PathBuf>,
+    /// Seed of the generated city that was being played, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Tuning when recording started. (Replays from before tuning was saved
+    /// use the defaults.)
+    #[serde(default)]
+    pub tuning: Tuning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTick {
+    pub delta_time: f32,
+    pub player_actions: Vec<PlayerAction>,
+    /// `GameState::checksum` after this tick was simulated.
+    pub checksum: u64,
+}
+
+impl RecordedTick {
+    pub fn input_frame(&self) -> InputFrame {
+        InputFrame {
+            player_actions: self.player_actions.clone(),
+        }
+    }
+}
+
+pub struct Replay {
+    pub header: ReplayHeader,
+    pub ticks: Vec<RecordedTick>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// `line` starts at 1.
+    Parse {
+        line: usize,
+        error: serde_json::Error,
+    },
+    Empty,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "couldn't read replay: {}", error),
+            ReplayError::Parse { line, error } => {
+                write!(f, "bad replay data on line {}: {}", line, error)
+            }
+            ReplayError::Empty => write!(f, "replay file is empty"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+/// Where a replay stopped matching the recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayMismatch {
+    /// Index into `Replay::ticks`.
+    pub tick: usize,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "replay diverged on tick {}: expected checksum {:016x}, got {:016x}",
+            self.tick, self.expected, self.actual
+        )
+    }
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = reader.lines();
+
+        let header_line = lines.next().ok_or(ReplayError::Empty)??;
+        let header = serde_json::from_str(&header_line)
+            .map_err(|error| ReplayError::Parse { line: 1, error })?;
+
+        let mut ticks = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let tick = serde_json::from_str(&line).map_err(|error| ReplayError::Parse {
+                line: index + 2,
+                error,
+            })?;
+            ticks.push(tick);
+        }
+
+        Ok(Self { header, ticks })
+    }
+
+    /// Fresh game state matching how the recording started.
+    pub fn new_game_state(&self) -> Result<GameState, LevelError> {
+        let mut game_state = match (&self.header.level, self.header.seed) {
+            (Some(path), _) => GameState::from_level(&Level::load(path)?),
+            (None, Some(seed)) => GameState::from_level(&generate_city(seed)),
+            (None, None) => GameState::new(Player::new(self.header.spawn.x, self.header.spawn.y)),
+        };
+        game_state.set_tuning(self.header.tuning.clone());
+
+        Ok(game_state)
+    }
+
+    /// Runs the whole replay on `game_state` (see `new_game_state`) and checks
+    /// the player ends up in exactly the same state, tick by tick.
+    pub fn verify(&self, mut game_state: GameState) -> Result<GameState, ReplayMismatch> {
+        for (index, tick) in self.ticks.iter().enumerate() {
+            simulate(&tick.input_frame(), &mut game_state, tick.delta_time);
+
+            let actual = game_state.checksum();
+            if actual != tick.checksum {
+                return Err(ReplayMismatch {
+                    tick: index,
+                    expected: tick.checksum,
+                    actual,
+                });
+            }
+        }
+
+        Ok(game_state)
+    }
+}
+
+/// Writes every simulated tick to a replay file as it happens.
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: impl AsRef<Path>, header: &ReplayHeader) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, header)?;
+        writeln!(writer)?;
+
+        Ok(Self { writer })
+    }
+
+    /// Call right after `simulate`, with the same input and delta time.
+    pub fn record(
+        &mut self,
+        input_frame: &InputFrame,
+        delta_time: f32,
+        game_state: &GameState,
+    ) -> io::Result<()> {
+        let tick = RecordedTick {
+            delta_time,
+            player_actions: input_frame.player_actions.clone(),
+            checksum: game_state.checksum(),
+        };
+        serde_json::to_writer(&mut self.writer, &tick)?;
+        writeln!(self.writer)?;
+
+        // Flushing every tick so nothing is lost if the game is closed
+        // (or crashes, which is usually why we want the replay).
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,259 @@
+//! The road network, for finding a route to wherever the taxi's going. Roads
+//! are two way and go in straight lines between nodes. In a level file:
+//!
+//! ```ron
+//! roads: (
+//!     nodes: [(x: 0.0, y: 0.0), (x: 0.0, y: -500.0), (x: 600.0, y: -500.0)],
+//!     edges: [(from: 0, to: 1), (from: 1, to: 2, lanes: 4, lane_width: 70.0)],
+//! ),
+//! ```
+//!
+//! Nothing stops the taxi leaving the road; this is just for directions.
+
+use crate::constants::{ROAD_LANE_WIDTH, ROAD_LANES};
+use crate::math::{Point, closest_point_on_segment};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoadGraph {
+    pub nodes: Vec<Point>,
+    #[serde(default)]
+    pub edges: Vec<Road>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Road {
+    /// Indexes into `RoadGraph::nodes`.
+    pub from: usize,
+    pub to: usize,
+    /// Counting both directions.
+    #[serde(default = "default_lanes")]
+    pub lanes: u32,
+    #[serde(default = "default_lane_width")]
+    pub lane_width: f32,
+}
+
+fn default_lanes() -> u32 {
+    ROAD_LANES
+}
+
+fn default_lane_width() -> f32 {
+    ROAD_LANE_WIDTH
+}
+
+impl Road {
+    /// With the default lanes, same as a level file leaving them out.
+    pub fn new(from: usize, to: usize) -> Self {
+        Self {
+            from,
+            to,
+            lanes: ROAD_LANES,
+            lane_width: ROAD_LANE_WIDTH,
+        }
+    }
+
+    /// Edge to edge.
+    pub fn width(&self) -> f32 {
+        self.lanes as f32 * self.lane_width
+    }
+}
+
+/// Directions from one place to another along the roads.
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Starts at where the route was asked for from, then joins the nearest
+    /// road, follows it through every turn and leaves it again at the end.
+    pub points: Vec<Point>,
+    /// Total length of `points`.
+    pub length: f32,
+}
+
+impl Route {
+    /// Next place to head for from `from`: the first point past the part of
+    /// the route `from` is closest to, skipping any closer than `distance`.
+    /// None if the rest of the route is closer than that.
+    pub fn next_turn(&self, from: &Point, distance: f32) -> Option<Point> {
+        let closest_segment = self
+            .points
+            .windows(2)
+            .map(|pair| (closest_point_on_segment(from, &pair[0], &pair[1]) - *from).length())
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(index, _)| index);
+
+        self.points[closest_segment + 1..]
+            .iter()
+            .copied()
+            .find(|point| (*point - *from).length() > distance)
+    }
+}
+
+/// Where a position joins the road network.
+struct RoadAccess {
+    /// Index into `RoadGraph::edges` of the closest road.
+    edge: usize,
+    /// Closest point on that road.
+    point: Point,
+}
+
+impl RoadGraph {
+    /// What's wrong with the edge at `index`, if anything.
+    pub fn validate_edge(&self, index: usize) -> Result<(), &'static str> {
+        let edge = &self.edges[index];
+        if edge.from >= self.nodes.len() || edge.to >= self.nodes.len() {
+            return Err("goes to a node that doesn't exist");
+        }
+        if edge.from == edge.to {
+            return Err("goes from a node to itself");
+        }
+        if edge.lanes == 0 || edge.lane_width <= 0.0 {
+            return Err("needs at least one lane with a positive width");
+        }
+        Ok(())
+    }
+
+    /// Shortest way from `from` to `to` along the roads (A*). The ends are
+    /// joined to whichever road is closest to them. None if there are no
+    /// roads or they aren't connected.
+    pub fn find_route(&self, from: &Point, to: &Point) -> Option<Route> {
+        let start = self.closest_road(from)?;
+        let goal = self.closest_road(to)?;
+
+        // Nodes after the real ones: where the start and goal join the road.
+        let start_node = self.nodes.len();
+        let goal_node = self.nodes.len() + 1;
+        let position = |node: usize| {
+            if node == start_node {
+                start.point
+            } else if node == goal_node {
+                goal.point
+            } else {
+                self.nodes[node]
+            }
+        };
+        let neighbours = |node: usize| -> Vec<usize> {
+            let mut neighbours = Vec::new();
+            if node == start_node {
+                let edge = &self.edges[start.edge];
+                neighbours.extend([edge.from, edge.to]);
+                if start.edge == goal.edge {
+                    neighbours.push(goal_node);
+                }
+                return neighbours;
+            }
+
+            for (index, edge) in self.edges.iter().enumerate() {
+                if edge.from == node {
+                    neighbours.push(edge.to);
+                } else if edge.to == node {
+                    neighbours.push(edge.from);
+                } else {
+                    continue;
+                }
+                if index == goal.edge {
+                    neighbours.push(goal_node);
+                }
+            }
+            neighbours
+        };
+
+        let node_count = self.nodes.len() + 2;
+        let mut cost = vec![f32::INFINITY; node_count];
+        let mut came_from = vec![None; node_count];
+        let mut open = BinaryHeap::new();
+        cost[start_node] = 0.0;
+        open.push(OpenNode {
+            node: start_node,
+            estimate: (goal.point - start.point).length(),
+        });
+
+        while let Some(OpenNode { node, .. }) = open.pop() {
+            if node == goal_node {
+                break;
+            }
+
+            for neighbour in neighbours(node) {
+                let new_cost = cost[node] + (position(neighbour) - position(node)).length();
+                if new_cost < cost[neighbour] {
+                    cost[neighbour] = new_cost;
+                    came_from[neighbour] = Some(node);
+                    open.push(OpenNode {
+                        node: neighbour,
+                        // Straight line is never longer than the road.
+                        estimate: new_cost + (goal.point - position(neighbour)).length(),
+                    });
+                }
+            }
+        }
+
+        if cost[goal_node].is_infinite() {
+            return None;
+        }
+
+        let mut points = vec![*to];
+        let mut node = Some(goal_node);
+        while let Some(current) = node {
+            points.push(position(current));
+            node = came_from[current];
+        }
+        points.push(*from);
+        points.reverse();
+        // Ends that were already on the road join it at the same spot.
+        points.dedup_by(|a, b| (*a - *b).length() < 0.01);
+
+        let length = points
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).length())
+            .sum();
+        Some(Route { points, length })
+    }
+
+    fn closest_road(&self, position: &Point) -> Option<RoadAccess> {
+        self.edges
+            .iter()
+            .enumerate()
+            .map(|(edge, road)| RoadAccess {
+                edge,
+                point: closest_point_on_segment(
+                    position,
+                    &self.nodes[road.from],
+                    &self.nodes[road.to],
+                ),
+            })
+            .min_by(|a, b| {
+                let a_distance = (a.point - *position).length();
+                let b_distance = (b.point - *position).length();
+                a_distance.total_cmp(&b_distance)
+            })
+    }
+}
+
+/// Node waiting to be looked at, cheapest estimate first.
+struct OpenNode {
+    node: usize,
+    /// Cost so far plus the straight line distance to the goal.
+    estimate: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed since `BinaryHeap` pops the biggest first.
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
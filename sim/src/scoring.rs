@@ -0,0 +1,210 @@
+//! Points for driving with style: perfectly timed dashes, near misses with
+//! walls, long drifts and pickups straight out of high speed. Every trick
+//! bumps up a combo multiplier, which wears off again if another one doesn't
+//! come along soon enough.
+//!
+//! `simulate` runs this at the end of every tick, going off the taxi and the
+//! tick's events, and adds a `GameEvent::Scored` for each trick.
+
+use crate::constants::*;
+use crate::events::{GameEvent, Obstacle};
+use crate::gamestate::GameState;
+use crate::math::{Rect, polygon_distance};
+use crate::player::{DashTiming, Player};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trick {
+    PerfectDash,
+    NearMiss,
+    Drift,
+    HighSpeedPickup,
+}
+
+impl Trick {
+    /// For score pop-ups.
+    pub fn name(self) -> &'static str {
+        match self {
+            Trick::PerfectDash => "PERFECT DASH",
+            Trick::NearMiss => "NEAR MISS",
+            Trick::Drift => "DRIFT",
+            Trick::HighSpeedPickup => "HOT PICKUP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scoring {
+    /// Points so far this run.
+    pub total: u32,
+    /// What the next trick's points get multiplied by, 1.0 to `COMBO_MAX`.
+    pub combo: f32,
+    pub last_trick_at: Option<f64>,
+    /// When the current drift started, if the taxi's drifting.
+    drift_started_at: Option<f64>,
+    /// Closest the taxi's got to a wall on the near miss it's in the middle
+    /// of, if it is.
+    near_miss_gap: Option<f32>,
+    /// Touched a wall and hasn't been `NEAR_MISS_DISTANCE` clear of them
+    /// since, so anything close is a scrape rather than a near miss.
+    scraping: bool,
+    /// Last time the taxi was going at least `HIGH_SPEED_PICKUP_SPEED`.
+    last_fast_at: Option<f64>,
+}
+
+impl Scoring {
+    pub fn new() -> Self {
+        Self {
+            total: 0,
+            combo: 1.0,
+            last_trick_at: None,
+            drift_started_at: None,
+            near_miss_gap: None,
+            scraping: false,
+            last_fast_at: None,
+        }
+    }
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Looks for tricks on the tick that just ran (so after everything else in
+/// `simulate`) and scores them.
+pub fn simulate_scoring(game_state: &mut GameState, delta_time: f32) {
+    let time = game_state.time;
+    let player = &game_state.player;
+    let scoring = &game_state.scoring;
+    let mut tricks = Vec::new();
+
+    let mut hit_wall = false;
+    let mut crashed = false;
+    for event in game_state.events.iter() {
+        match event {
+            GameEvent::DashStarted {
+                timing: DashTiming::Perfect,
+                chain,
+                ..
+            } => tricks.push((Trick::PerfectDash, perfect_dash_points(player, *chain))),
+            GameEvent::Collision {
+                impact_speed, with, ..
+            } => {
+                hit_wall |= *with == Obstacle::Wall;
+                crashed |= *impact_speed >= DAMAGE_MIN_IMPACT_SPEED;
+            }
+            GameEvent::Wrecked => crashed = true,
+            GameEvent::PassengerPickedUp { .. } => {
+                let was_fast = scoring
+                    .last_fast_at
+                    .is_some_and(|at| time - at <= HIGH_SPEED_PICKUP_WINDOW);
+                if was_fast {
+                    tricks.push((Trick::HighSpeedPickup, HIGH_SPEED_PICKUP_POINTS));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let speed = player.speed();
+    let last_fast_at = if speed >= HIGH_SPEED_PICKUP_SPEED {
+        Some(time)
+    } else {
+        scoring.last_fast_at
+    };
+
+    // Crashing ends a drift without any points for it.
+    let drift_started_at = match scoring.drift_started_at {
+        _ if crashed => None,
+        Some(started_at) if !player.drifting => {
+            let drift_time = time - started_at;
+            if drift_time >= DRIFT_MIN_TIME {
+                tricks.push((Trick::Drift, DRIFT_POINTS_PER_SECOND * drift_time as f32));
+            }
+            None
+        }
+        None if player.drifting => Some(time),
+        started_at => started_at,
+    };
+
+    // Near misses are scored once the taxi's clear of the wall again, on how
+    // close it got. Touching the wall doesn't count, and neither does pulling
+    // away from it after (being pushed out leaves the taxi just off it).
+    let gap = closest_wall_gap(game_state);
+    let clear = gap.is_none_or(|gap| gap >= NEAR_MISS_DISTANCE);
+    let scraping = hit_wall || (scoring.scraping && !clear);
+    let near_miss_gap = match (gap, scoring.near_miss_gap) {
+        _ if scraping || speed < NEAR_MISS_MIN_SPEED => None,
+        (Some(gap), closest) if gap < NEAR_MISS_DISTANCE => {
+            Some(closest.map_or(gap, |closest| closest.min(gap)))
+        }
+        (_, Some(closest)) => {
+            let closeness = 1.0 - closest / NEAR_MISS_DISTANCE;
+            tricks.push((Trick::NearMiss, NEAR_MISS_POINTS * (1.0 + closeness)));
+            None
+        }
+        (_, None) => None,
+    };
+
+    let scoring = &mut game_state.scoring;
+    scoring.last_fast_at = last_fast_at;
+    scoring.drift_started_at = drift_started_at;
+    scoring.near_miss_gap = near_miss_gap;
+    scoring.scraping = scraping;
+
+    let combo_held = scoring
+        .last_trick_at
+        .is_some_and(|at| time - at < COMBO_HOLD_TIME);
+    if crashed {
+        scoring.combo = 1.0;
+    } else if !combo_held {
+        scoring.combo = (scoring.combo - COMBO_DECAY_RATE * delta_time).max(1.0);
+    }
+
+    for (trick, points) in tricks {
+        let scoring = &mut game_state.scoring;
+        let points = (points * scoring.combo).round() as u32;
+        scoring.total += points;
+        game_state.events.push(GameEvent::Scored {
+            trick,
+            points,
+            combo: scoring.combo,
+            position: game_state.player.center,
+        });
+        scoring.combo = (scoring.combo + COMBO_STEP).min(COMBO_MAX);
+        scoring.last_trick_at = Some(time);
+    }
+}
+
+/// More for longer chains, and for hitting the middle of the perfect timing
+/// window rather than just getting inside it.
+fn perfect_dash_points(player: &Player, chain: u32) -> f32 {
+    let window = &player.tuning.crazy_dash_perfect_timing;
+    let middle = (window.start + window.end) / 2.0;
+    let half_width = (window.end - window.start) / 2.0;
+    let off_by = (player.time_between_drive_and_gas - middle).abs();
+    let bullseye = if half_width > 0.0 {
+        (1.0 - off_by / half_width).clamp(0.0, 1.0) as f32
+    } else {
+        1.0
+    };
+    PERFECT_DASH_POINTS * chain as f32 + PERFECT_DASH_BULLSEYE_POINTS * bullseye
+}
+
+/// Gap between the taxi and the closest wall within `NEAR_MISS_DISTANCE` of
+/// it, if there is one.
+fn closest_wall_gap(game_state: &GameState) -> Option<f32> {
+    let player = &game_state.player;
+    let bounds = player.bounds();
+    let area = Rect::new(
+        bounds.x - NEAR_MISS_DISTANCE,
+        bounds.y - NEAR_MISS_DISTANCE,
+        bounds.w + NEAR_MISS_DISTANCE * 2.0,
+        bounds.h + NEAR_MISS_DISTANCE * 2.0,
+    );
+    game_state
+        .walls_in(&area)
+        .map(|wall| polygon_distance(&player.points, &wall.points))
+        .min_by(|a, b| a.total_cmp(b))
+}
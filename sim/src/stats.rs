@@ -0,0 +1,53 @@
+//! Running totals for a session, added up from `GameState::events`.
+
+use crate::events::GameEvent;
+use crate::player::DashTiming;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub dashes: u32,
+    pub perfect_dashes: u32,
+    pub longest_dash_chain: u32,
+    pub gear_changes: u32,
+    pub collisions: u32,
+    /// Biggest `impact_speed` of any collision.
+    pub hardest_hit: f32,
+    pub wrecks: u32,
+    pub pickups: u32,
+    pub drop_offs: u32,
+    /// See `scoring.rs`.
+    pub tricks: u32,
+    /// Highest combo any trick was scored at.
+    pub best_combo: f32,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::DashStarted { timing, chain, .. } => {
+                self.dashes += 1;
+                if *timing == DashTiming::Perfect {
+                    self.perfect_dashes += 1;
+                }
+                self.longest_dash_chain = self.longest_dash_chain.max(*chain);
+            }
+            GameEvent::GearChanged { .. } => self.gear_changes += 1,
+            GameEvent::Collision { impact_speed, .. } => {
+                self.collisions += 1;
+                self.hardest_hit = self.hardest_hit.max(*impact_speed);
+            }
+            GameEvent::Wrecked => self.wrecks += 1,
+            GameEvent::PassengerPickedUp { .. } => self.pickups += 1,
+            GameEvent::PassengerDroppedOff(_) => self.drop_offs += 1,
+            GameEvent::Scored { combo, .. } => {
+                self.tricks += 1;
+                self.best_combo = self.best_combo.max(*combo);
+            }
+            GameEvent::DashEnded | GameEvent::DashDenied(_) | GameEvent::Towed => (),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Grid of road, sidewalk and building tiles, stored in square chunks of
+//! `CHUNK_TILES` tiles so big cities don't need every building loaded at
+//! once. Tile `(0, 0)` has its top-left corner at the world origin.
+//!
+//! The tiles themselves are just data; `GameState::stream_tilemap` turns the
+//! buildings in nearby chunks into `Wall`s (see `Chunk::building_outlines`)
+//! and drops them again once they're far away.
+
+use crate::constants::{CHUNK_TILES, TILE_SIZE};
+use crate::math::{Point, Rect};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tile {
+    /// Nothing there, just the ground.
+    #[default]
+    Empty,
+    Road,
+    Sidewalk,
+    /// Solid, gets a wall.
+    Building,
+}
+
+impl Tile {
+    /// How tiles are written in level files.
+    pub fn from_char(character: char) -> Option<Self> {
+        match character {
+            ' ' => Some(Tile::Empty),
+            '.' => Some(Tile::Road),
+            '_' => Some(Tile::Sidewalk),
+            '#' => Some(Tile::Building),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Empty => ' ',
+            Tile::Road => '.',
+            Tile::Sidewalk => '_',
+            Tile::Building => '#',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkCoord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl ChunkCoord {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// The chunk that the tile at `(tile_x, tile_y)` is in.
+    pub fn of_tile(tile_x: i32, tile_y: i32) -> Self {
+        Self::new(
+            tile_x.div_euclid(CHUNK_TILES as i32),
+            tile_y.div_euclid(CHUNK_TILES as i32),
+        )
+    }
+
+    /// Tile coordinates of the chunk's top-left tile.
+    pub fn first_tile(&self) -> (i32, i32) {
+        (self.x * CHUNK_TILES as i32, self.y * CHUNK_TILES as i32)
+    }
+
+    /// World space area the chunk covers.
+    pub fn bounds(&self) -> Rect {
+        let size = CHUNK_TILES as f32 * TILE_SIZE;
+        Rect::new(self.x as f32 * size, self.y as f32 * size, size, size)
+    }
+}
+
+/// `CHUNK_TILES` by `CHUNK_TILES` tiles, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    tiles: Vec<Tile>,
+}
+
+impl Chunk {
+    fn new() -> Self {
+        Self {
+            tiles: vec![Tile::Empty; CHUNK_TILES * CHUNK_TILES],
+        }
+    }
+
+    /// `x` and `y` are from the chunk's top-left, up to `CHUNK_TILES`.
+    pub fn get(&self, x: usize, y: usize) -> Tile {
+        self.tiles[y * CHUNK_TILES + x]
+    }
+
+    fn set(&mut self, x: usize, y: usize, tile: Tile) {
+        self.tiles[y * CHUNK_TILES + x] = tile;
+    }
+
+    /// Building tiles merged into as few rectangles as it can easily manage,
+    /// as world space outlines ready for `Wall::from_points`. Buildings don't
+    /// get merged across chunks, so each chunk's walls can come and go on
+    /// their own.
+    pub fn building_outlines(&self, coord: ChunkCoord) -> Vec<Vec<Point>> {
+        let mut used = vec![false; self.tiles.len()];
+        let is_free_building = |used: &[bool], x: usize, y: usize| {
+            !used[y * CHUNK_TILES + x] && self.get(x, y) == Tile::Building
+        };
+        let (first_x, first_y) = coord.first_tile();
+        let mut outlines = Vec::new();
+
+        for y in 0..CHUNK_TILES {
+            for x in 0..CHUNK_TILES {
+                if !is_free_building(&used, x, y) {
+                    continue;
+                }
+
+                // As wide as the row goes, then as far down as every tile
+                // under it is a building too.
+                let mut width = 1;
+                while x + width < CHUNK_TILES && is_free_building(&used, x + width, y) {
+                    width += 1;
+                }
+                let mut height = 1;
+                while y + height < CHUNK_TILES
+                    && (x..x + width).all(|column| is_free_building(&used, column, y + height))
+                {
+                    height += 1;
+                }
+
+                for row in y..y + height {
+                    for column in x..x + width {
+                        used[row * CHUNK_TILES + column] = true;
+                    }
+                }
+
+                let left = (first_x + x as i32) as f32 * TILE_SIZE;
+                let top = (first_y + y as i32) as f32 * TILE_SIZE;
+                let right = left + width as f32 * TILE_SIZE;
+                let bottom = top + height as f32 * TILE_SIZE;
+                outlines.push(vec![
+                    Point::new(left, top),
+                    Point::new(right, top),
+                    Point::new(right, bottom),
+                    Point::new(left, bottom),
+                ]);
+            }
+        }
+
+        outlines
+    }
+}
+
+/// Chunks that don't exist are all `Tile::Empty`, so the map goes on forever
+/// without storing anything for the empty parts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tilemap {
+    chunks: BTreeMap<ChunkCoord, Chunk>,
+}
+
+impl Tilemap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One string per row of tiles (see `Tile::from_char`), with the first
+    /// row's first tile at `origin` (in tiles). On a character it doesn't
+    /// know, returns the row and column it's at.
+    pub fn from_rows(origin: (i32, i32), rows: &[String]) -> Result<Self, (usize, usize)> {
+        let mut tilemap = Self::new();
+        for (row_index, row) in rows.iter().enumerate() {
+            for (column, character) in row.chars().enumerate() {
+                let tile = Tile::from_char(character).ok_or((row_index, column))?;
+                tilemap.set(origin.0 + column as i32, origin.1 + row_index as i32, tile);
+            }
+        }
+        Ok(tilemap)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn get(&self, tile_x: i32, tile_y: i32) -> Tile {
+        let coord = ChunkCoord::of_tile(tile_x, tile_y);
+        let (first_x, first_y) = coord.first_tile();
+        self.chunks.get(&coord).map_or(Tile::Empty, |chunk| {
+            chunk.get((tile_x - first_x) as usize, (tile_y - first_y) as usize)
+        })
+    }
+
+    pub fn set(&mut self, tile_x: i32, tile_y: i32, tile: Tile) {
+        let coord = ChunkCoord::of_tile(tile_x, tile_y);
+        let (first_x, first_y) = coord.first_tile();
+        // Don't make a chunk just to hold nothing.
+        if tile == Tile::Empty && !self.chunks.contains_key(&coord) {
+            return;
+        }
+        self.chunks.entry(coord).or_insert_with(Chunk::new).set(
+            (tile_x - first_x) as usize,
+            (tile_y - first_y) as usize,
+            tile,
+        );
+    }
+
+    /// Tile coordinates of whatever tile `point` is on.
+    pub fn tile_at(point: &Point) -> (i32, i32) {
+        (
+            (point.x / TILE_SIZE).floor() as i32,
+            (point.y / TILE_SIZE).floor() as i32,
+        )
+    }
+
+    /// World space area of a tile.
+    pub fn tile_bounds(tile_x: i32, tile_y: i32) -> Rect {
+        Rect::new(
+            tile_x as f32 * TILE_SIZE,
+            tile_y as f32 * TILE_SIZE,
+            TILE_SIZE,
+            TILE_SIZE,
+        )
+    }
+
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&Chunk> {
+        self.chunks.get(&coord)
+    }
+
+    /// Every chunk that exists and overlaps `area`, in order.
+    pub fn chunks_in(&self, area: &Rect) -> impl Iterator<Item = (ChunkCoord, &Chunk)> {
+        let (min_x, min_y) = Self::tile_at(&Point::new(area.x, area.y));
+        let (max_x, max_y) = Self::tile_at(&Point::new(area.x + area.w, area.y + area.h));
+        let min = ChunkCoord::of_tile(min_x, min_y);
+        let max = ChunkCoord::of_tile(max_x, max_y);
+
+        self.chunks
+            .range(ChunkCoord::new(min.x, i32::MIN)..=ChunkCoord::new(max.x, i32::MAX))
+            .filter(move |(coord, _)| coord.y >= min.y && coord.y <= max.y)
+            .map(|(coord, chunk)| (*coord, chunk))
+    }
+
+    /// Every tile that isn't empty and overlaps `area`, with its tile
+    /// coordinates. For drawing.
+    pub fn tiles_in(&self, area: &Rect) -> impl Iterator<Item = (i32, i32, Tile)> {
+        let (min_x, min_y) = Self::tile_at(&Point::new(area.x, area.y));
+        let (max_x, max_y) = Self::tile_at(&Point::new(area.x + area.w, area.y + area.h));
+
+        self.chunks_in(area).flat_map(move |(coord, chunk)| {
+            let (first_x, first_y) = coord.first_tile();
+            (0..CHUNK_TILES * CHUNK_TILES).filter_map(move |index| {
+                let (x, y) = (index % CHUNK_TILES, index / CHUNK_TILES);
+                let (tile_x, tile_y) = (first_x + x as i32, first_y + y as i32);
+                let inside = (min_x..=max_x).contains(&tile_x) && (min_y..=max_y).contains(&tile_y);
+                let tile = chunk.get(x, y);
+                (inside && tile != Tile::Empty).then_some((tile_x, tile_y, tile))
+            })
+        })
+    }
+}
@@ -0,0 +1,38 @@
+/// Accumulates real frame time and hands it out in fixed size simulation
+/// ticks, so physics behaves the same no matter the frame rate.
+pub struct FixedTimestep {
+    /// Length of one simulation tick in seconds.
+    pub tick_delta_time: f32,
+    accumulator: f32,
+}
+
+/// Cap on how much frame time gets simulated at once. Stops the game from
+/// trying to catch up forever after a long hitch (e.g. dragging the window).
+const MAX_FRAME_TIME: f32 = 0.25;
+
+impl FixedTimestep {
+    pub fn new(ticks_per_second: f32) -> Self {
+        Self {
+            tick_delta_time: 1.0 / ticks_per_second,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Adds this frame's time and returns how many ticks should be simulated.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_delta_time {
+            self.accumulator -= self.tick_delta_time;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// How far we are between the last tick and the next one (0.0 to 1.0).
+    /// Used to interpolate rendering between simulation ticks.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.tick_delta_time
+    }
+}
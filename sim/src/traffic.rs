@@ -0,0 +1,264 @@
+//! AI traffic. Every car is a `Player` with the same physics as the taxi,
+//! driven by `PlayerAction`s the AI comes up with each tick instead of the
+//! keyboard. Cars drive around the level's lanes, slowing down for anything
+//! in front of them.
+
+use crate::constants::*;
+use crate::events::{GameEvent, Obstacle};
+use crate::gamestate::GameState;
+use crate::level::Lane;
+use crate::math::{Point, polygon_collision};
+use crate::player::{Player, PlayerAction};
+use std::f32::consts::PI;
+use std::iter;
+
+pub struct TrafficCar {
+    pub car: Player,
+    /// Index into `GameState::lanes`.
+    pub lane: usize,
+    /// Index into the lane's points of the waypoint it's heading for.
+    pub next_waypoint: usize,
+    /// When it was wrecked, if it is. Towed after `TOW_DELAY` so it doesn't
+    /// block the lane for good.
+    pub wrecked_at: Option<f64>,
+}
+
+impl TrafficCar {
+    /// Starts on `waypoint`, facing the one after it.
+    pub fn new(lanes: &[Lane], lane: usize, waypoint: usize) -> Self {
+        let points = &lanes[lane].points;
+        let start = points[waypoint];
+        let next_waypoint = (waypoint + 1) % points.len();
+
+        let mut car = Player::new(start.x, start.y);
+        car.set_rotation(heading_towards(&start, &points[next_waypoint]));
+
+        Self {
+            car,
+            lane,
+            next_waypoint,
+            wrecked_at: None,
+        }
+    }
+
+    /// What the AI wants to do this tick. `others` is every other car on
+    /// the road, including the taxi.
+    pub fn drive<'a>(
+        &self,
+        lane: &Lane,
+        others: impl Iterator<Item = &'a Player>,
+    ) -> Vec<PlayerAction> {
+        let waypoint = lane.points[self.next_waypoint];
+        let angle = signed_angle(&self.car.forward_normal, &(waypoint - self.car.center));
+
+        // Slow down for corners, and more so for whatever's ahead.
+        let corner = (1.0 - angle.abs() / PI).max(TRAFFIC_CORNER_SLOWDOWN);
+        let mut target_speed = TRAFFIC_CRUISE_SPEED * corner;
+        if let Some(distance) = distance_to_car_ahead(&self.car, others) {
+            let clear =
+                (distance - TRAFFIC_STOP_DISTANCE) / (TRAFFIC_LOOK_AHEAD - TRAFFIC_STOP_DISTANCE);
+            target_speed = target_speed.min(TRAFFIC_CRUISE_SPEED * clear.clamp(0.0, 1.0));
+        }
+
+        let mut actions = Vec::new();
+        let forward_speed = self.car.forward_speed();
+        if forward_speed > target_speed {
+            actions.push(PlayerAction::Brake);
+        } else if forward_speed < target_speed {
+            actions.push(PlayerAction::GasHeld);
+        }
+
+        let steer = (angle * TRAFFIC_STEER_GAIN).clamp(-1.0, 1.0);
+        if steer != 0.0 {
+            actions.push(PlayerAction::Steer(steer));
+        }
+
+        actions
+    }
+
+    /// Moves on to the next waypoint once this one's reached, or if it was
+    /// missed (it's close but already behind the car).
+    fn update_waypoint(&mut self, lane: &Lane) {
+        let to_waypoint = lane.points[self.next_waypoint] - self.car.center;
+        let distance = to_waypoint.length();
+        let behind = to_waypoint.dot(&self.car.forward_normal) < 0.0;
+
+        if distance < TRAFFIC_WAYPOINT_RADIUS
+            || (behind && distance < TRAFFIC_WAYPOINT_RADIUS * 2.0)
+        {
+            self.next_waypoint = (self.next_waypoint + 1) % lane.points.len();
+        }
+    }
+}
+
+/// Rotation (see `Player::set_rotation`) that faces from `from` to `to`.
+fn heading_towards(from: &Point, to: &Point) -> f32 {
+    let direction = *to - *from;
+    direction.x.atan2(-direction.y)
+}
+
+/// Angle to turn `forward` by to face `direction`, in radians. Positive is
+/// clockwise (to the right).
+fn signed_angle(forward: &Point, direction: &Point) -> f32 {
+    let cross = forward.x * direction.y - forward.y * direction.x;
+    cross.atan2(forward.dot(direction))
+}
+
+/// Center to center distance to the closest car in front of `car`, roughly
+/// in its path. None if the road ahead is clear.
+fn distance_to_car_ahead<'a>(
+    car: &Player,
+    others: impl Iterator<Item = &'a Player>,
+) -> Option<f32> {
+    let right_normal = Point::new(-car.forward_normal.y, car.forward_normal.x);
+
+    others
+        .filter_map(|other| {
+            let offset = other.center - car.center;
+            let ahead = offset.dot(&car.forward_normal);
+            let side = offset.dot(&right_normal);
+            (ahead > 0.0 && ahead < TRAFFIC_LOOK_AHEAD && side.abs() < TRAFFIC_LANE_HALF_WIDTH)
+                .then_some(ahead)
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Spawns the cars each lane asks for, spread out evenly between its points.
+/// They're tuned the same as the taxi.
+pub fn spawn_traffic(game_state: &mut GameState) {
+    for (lane_index, lane) in game_state.lanes.iter().enumerate() {
+        for car in 0..lane.cars {
+            let waypoint = car * lane.points.len() / lane.cars;
+            let mut traffic_car = TrafficCar::new(&game_state.lanes, lane_index, waypoint);
+            traffic_car.car.set_tuning(game_state.player.tuning.clone());
+            game_state.traffic.push(traffic_car);
+        }
+    }
+}
+
+/// Wrecks sit where they are for `TOW_DELAY`, like the taxi, then get put
+/// back on their lane good as new, wherever there's the most room.
+fn tow_wrecked_traffic(game_state: &mut GameState) {
+    for index in 0..game_state.traffic.len() {
+        let traffic_car = &mut game_state.traffic[index];
+        if !traffic_car.car.is_wrecked() {
+            traffic_car.wrecked_at = None;
+            continue;
+        }
+        let wrecked_at = *traffic_car.wrecked_at.get_or_insert(game_state.time);
+        if game_state.time - wrecked_at < TOW_DELAY {
+            continue;
+        }
+
+        let lane = traffic_car.lane;
+        let waypoint = roomiest_waypoint(game_state, lane, index);
+        let mut towed = TrafficCar::new(&game_state.lanes, lane, waypoint);
+        towed.car.set_tuning(game_state.player.tuning.clone());
+        game_state.traffic[index] = towed;
+    }
+}
+
+/// The lane's waypoint furthest from the taxi and every car but `skip`.
+fn roomiest_waypoint(game_state: &GameState, lane: usize, skip: usize) -> usize {
+    let others: Vec<Point> = game_state
+        .traffic
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != skip)
+        .map(|(_, other)| other.car.center)
+        .chain(iter::once(game_state.player.center))
+        .collect();
+    let room = |point: &Point| {
+        others
+            .iter()
+            .map(|other| (*other - *point).length())
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    let points = &game_state.lanes[lane].points;
+    (0..points.len())
+        .max_by(|a, b| room(&points[*a]).total_cmp(&room(&points[*b])))
+        .unwrap_or(0)
+}
+
+/// Runs after the taxi has moved. Anything the taxi hits gets added to
+/// `GameState::events`.
+pub fn simulate_traffic(game_state: &mut GameState, delta_time: f32) {
+    // Taken out for the tick, so the cars can move while looking at the rest
+    // of the world.
+    tow_wrecked_traffic(game_state);
+    let mut traffic = std::mem::take(&mut game_state.traffic);
+
+    for traffic_car in traffic.iter_mut() {
+        traffic_car.update_waypoint(&game_state.lanes[traffic_car.lane]);
+    }
+
+    // Decide everything up front, so every car sees where the others were at
+    // the start of the tick, whatever order they're in.
+    let all_actions: Vec<Vec<PlayerAction>> = traffic
+        .iter()
+        .enumerate()
+        .map(|(index, traffic_car)| {
+            let others = traffic
+                .iter()
+                .enumerate()
+                .filter(move |(other_index, _)| *other_index != index)
+                .map(|(_, other)| &other.car)
+                .chain(iter::once(&game_state.player));
+            traffic_car.drive(&game_state.lanes[traffic_car.lane], others)
+        })
+        .collect();
+
+    for (traffic_car, actions) in traffic.iter_mut().zip(all_actions) {
+        let car = &mut traffic_car.car;
+        car.save_previous_position();
+        // Nobody's listening to traffic.
+        car.events.clear();
+        for action in actions.iter() {
+            car.apply_action(action, game_state.time, delta_time);
+        }
+        car.simulate(delta_time, game_state.time);
+        game_state.resolve_wall_collisions(car);
+    }
+
+    let collisions = resolve_car_collisions(&mut game_state.player, &mut traffic);
+    game_state.events.extend(collisions);
+    game_state.traffic = traffic;
+}
+
+/// Cars against each other and against the taxi. Returns a `Collision` for
+/// each car the taxi hit.
+fn resolve_car_collisions(taxi: &mut Player, traffic: &mut [TrafficCar]) -> Vec<GameEvent> {
+    let mut taxi_collisions = Vec::new();
+
+    for index in 0..traffic.len() {
+        let (before, after) = traffic.split_at_mut(index + 1);
+        let car = &mut before[index].car;
+
+        for other in after.iter_mut() {
+            if !car.bounds().overlaps(&other.car.bounds()) {
+                continue;
+            }
+            if let Some(collision) = polygon_collision(&car.points, &other.car.points) {
+                car.resolve_car_collision(&mut other.car, &collision);
+            }
+        }
+
+        if !taxi.bounds().overlaps(&car.bounds()) {
+            continue;
+        }
+        let Some(collision) = polygon_collision(&taxi.points, &car.points) else {
+            continue;
+        };
+        let impact_speed = taxi.resolve_car_collision(car, &collision);
+        if impact_speed > 0.0 {
+            taxi_collisions.push(GameEvent::Collision {
+                normal: collision.normal,
+                impact_speed,
+                with: Obstacle::Traffic(index),
+            });
+        }
+    }
+
+    taxi_collisions
+}
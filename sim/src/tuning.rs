@@ -0,0 +1,267 @@
+//! Everything that changes how the taxi feels to drive, loaded from a TOML
+//! file so it can be tweaked without recompiling, e.g.
+//!
+//! ```toml
+//! gas_velocity = 960.0
+//! crazy_dash_velocity = 4000.0
+//!
+//! [player_shape]
+//! half_width = 30.0
+//! half_height = 40.0
+//! inner_space_w = 10.0
+//! inner_space_h = 15.0
+//! ```
+//!
+//! Anything left out uses the default from `constants.rs`. The same file's
+//! `[camera]` table is only for the game's camera, so it's ignored here (and
+//! doesn't end up in replays).
+
+use crate::constants::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// See `constants.rs` for what each of these does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    pub gas_velocity: f32,
+    pub crazy_dash_velocity: f32,
+    pub reverse_velocity: f32,
+
+    pub crazy_dash_length: f64,
+    pub crazy_dash_max_velocity: f32,
+    pub crazy_dash_drag: f32,
+    pub crazy_dash_drag_recovery: f32,
+    pub crazy_dash_input_timing: Range<f64>,
+    pub crazy_dash_perfect_timing: Range<f64>,
+    pub crazy_dash_cooldown: f64,
+    pub crazy_dash_chain_window: f64,
+    pub crazy_dash_chain_bonus: f32,
+    pub crazy_dash_max_chain: u32,
+    pub crazy_dash_boost_cost: f32,
+    pub crazy_dash_boost_regen: f32,
+
+    pub player_max_velocity: f32,
+    pub player_max_reverse_velocity: f32,
+    pub player_rotation_speed: f32,
+
+    pub reverse_engage_max_speed: f32,
+    pub park_engage_max_speed: f32,
+
+    pub brake_deceleration: f32,
+    pub brake_initial_strength: f32,
+    pub brake_ramp_time: f32,
+
+    pub tire_grip: f32,
+    pub tire_sliding_grip: f32,
+
+    pub car_default_drag: f32,
+    pub wall_restitution: f32,
+
+    pub player_shape: PlayerShapeConfig,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            gas_velocity: GAS_VELOCITY,
+            crazy_dash_velocity: CRAZY_DASH_VELOCITY,
+            reverse_velocity: REVERSE_VELOCITY,
+            crazy_dash_length: CRAZY_DASH_LENGTH,
+            crazy_dash_max_velocity: CRAZY_DASH_MAX_VELOCITY,
+            crazy_dash_drag: CRAZY_DASH_DRAG,
+            crazy_dash_drag_recovery: CRAZY_DASH_DRAG_RECOVERY,
+            crazy_dash_input_timing: CRAZY_DASH_INPUT_TIMING,
+            crazy_dash_perfect_timing: CRAZY_DASH_PERFECT_TIMING,
+            crazy_dash_cooldown: CRAZY_DASH_COOLDOWN,
+            crazy_dash_chain_window: CRAZY_DASH_CHAIN_WINDOW,
+            crazy_dash_chain_bonus: CRAZY_DASH_CHAIN_BONUS,
+            crazy_dash_max_chain: CRAZY_DASH_MAX_CHAIN,
+            crazy_dash_boost_cost: CRAZY_DASH_BOOST_COST,
+            crazy_dash_boost_regen: CRAZY_DASH_BOOST_REGEN,
+            player_max_velocity: PLAYER_MAX_VELOCITY,
+            player_max_reverse_velocity: PLAYER_MAX_REVERSE_VELOCITY,
+            player_rotation_speed: PLAYER_ROTATION_SPEED,
+            reverse_engage_max_speed: REVERSE_ENGAGE_MAX_SPEED,
+            park_engage_max_speed: PARK_ENGAGE_MAX_SPEED,
+            brake_deceleration: BRAKE_DECELERATION,
+            brake_initial_strength: BRAKE_INITIAL_STRENGTH,
+            brake_ramp_time: BRAKE_RAMP_TIME,
+            tire_grip: TIRE_GRIP,
+            tire_sliding_grip: TIRE_SLIDING_GRIP,
+            car_default_drag: CAR_DEFAULT_DRAG,
+            wall_restitution: WALL_RESTITUTION,
+            player_shape: PLAYER_SHAPE,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TuningError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    /// Parsed fine, but a value can't work, e.g. a top speed of 0.
+    Invalid {
+        field: &'static str,
+        problem: &'static str,
+    },
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TuningError::Io(error) => write!(f, "couldn't read tuning: {}", error),
+            TuningError::Parse(error) => write!(f, "couldn't parse tuning: {}", error),
+            TuningError::Invalid { field, problem } => {
+                write!(f, "bad tuning: {} {}", field, problem)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TuningError {}
+
+impl From<io::Error> for TuningError {
+    fn from(error: io::Error) -> Self {
+        TuningError::Io(error)
+    }
+}
+
+impl Tuning {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TuningError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TuningError> {
+        let tuning: Self = toml::from_str(text).map_err(TuningError::Parse)?;
+        tuning.validate()?;
+        Ok(tuning)
+    }
+
+    /// Catches values that would make the taxi undrivable (or divide by
+    /// zero), naming the first bad field.
+    pub fn validate(&self) -> Result<(), TuningError> {
+        let invalid = |field, problem| Err(TuningError::Invalid { field, problem });
+        let positive = [
+            ("gas_velocity", self.gas_velocity as f64),
+            ("crazy_dash_velocity", self.crazy_dash_velocity as f64),
+            ("reverse_velocity", self.reverse_velocity as f64),
+            ("crazy_dash_length", self.crazy_dash_length),
+            (
+                "crazy_dash_max_velocity",
+                self.crazy_dash_max_velocity as f64,
+            ),
+            ("crazy_dash_cooldown", self.crazy_dash_cooldown),
+            ("crazy_dash_chain_window", self.crazy_dash_chain_window),
+            ("player_max_velocity", self.player_max_velocity as f64),
+            ("player_rotation_speed", self.player_rotation_speed as f64),
+            ("brake_deceleration", self.brake_deceleration as f64),
+            ("brake_ramp_time", self.brake_ramp_time as f64),
+            ("tire_grip", self.tire_grip as f64),
+            (
+                "player_shape.half_width",
+                self.player_shape.half_width as f64,
+            ),
+            (
+                "player_shape.half_height",
+                self.player_shape.half_height as f64,
+            ),
+        ];
+        for (field, value) in positive {
+            if !value.is_finite() || value <= 0.0 {
+                return invalid(field, "has to be more than 0");
+            }
+        }
+
+        let not_negative = [
+            ("crazy_dash_drag", self.crazy_dash_drag),
+            ("crazy_dash_drag_recovery", self.crazy_dash_drag_recovery),
+            ("crazy_dash_chain_bonus", self.crazy_dash_chain_bonus),
+            ("crazy_dash_boost_cost", self.crazy_dash_boost_cost),
+            ("crazy_dash_boost_regen", self.crazy_dash_boost_regen),
+            ("reverse_engage_max_speed", self.reverse_engage_max_speed),
+            ("park_engage_max_speed", self.park_engage_max_speed),
+            ("brake_initial_strength", self.brake_initial_strength),
+            ("tire_sliding_grip", self.tire_sliding_grip),
+            ("car_default_drag", self.car_default_drag),
+            ("wall_restitution", self.wall_restitution),
+            (
+                "player_shape.inner_space_w",
+                self.player_shape.inner_space_w,
+            ),
+            (
+                "player_shape.inner_space_h",
+                self.player_shape.inner_space_h,
+            ),
+        ];
+        for (field, value) in not_negative {
+            if !value.is_finite() || value < 0.0 {
+                return invalid(field, "can't be negative");
+            }
+        }
+
+        let windows = [
+            ("crazy_dash_input_timing", &self.crazy_dash_input_timing),
+            ("crazy_dash_perfect_timing", &self.crazy_dash_perfect_timing),
+        ];
+        for (field, window) in windows {
+            if !(window.start >= 0.0 && window.start < window.end && window.end.is_finite()) {
+                return invalid(field, "has to go from 0 or more up to something bigger");
+            }
+        }
+        // Reversing speeds are negative.
+        let max_reverse = self.player_max_reverse_velocity;
+        if !max_reverse.is_finite() || max_reverse >= 0.0 {
+            return invalid("player_max_reverse_velocity", "has to be less than 0");
+        }
+        if self.crazy_dash_max_chain == 0 {
+            return invalid("crazy_dash_max_chain", "has to be at least 1");
+        }
+        Ok(())
+    }
+
+    /// The same format `parse` reads, with every value filled in.
+    pub fn to_toml(&self) -> String {
+        // Only fails for types TOML can't represent, and there aren't any here.
+        toml::to_string(self).expect("tuning should always serialize")
+    }
+}
+
+/// Reloads a tuning file whenever it changes on disk.
+///
+/// This just checks the modified time whenever `poll` is called (once a frame
+/// is plenty), which is simpler than a file watcher and cheap enough.
+pub struct TuningWatcher {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl TuningWatcher {
+    /// Doesn't load anything yet; the first `poll` will if the file exists.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            modified: None,
+        }
+    }
+
+    /// `Some` if the file changed since the last call (or this is the first
+    /// call and the file exists). A missing file is ignored, so it can be
+    /// created while the game is running.
+    pub fn poll(&mut self) -> Option<Result<Tuning, TuningError>> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        Some(Tuning::load(&self.path))
+    }
+}
@@ -1,24 +1,45 @@
-
-use crate::math::Point;
+use crate::math::{
+    Point, Rect, convex_decomposition, polygon_bounds, polygon_centroid, rotate_around_point,
+};
 
 pub struct Wall {
-    pub points: [Point; 6],
+    /// Outline of the wall, can be concave.
+    pub points: Vec<Point>,
     pub center: Point,
+    /// In radians. Already applied to `points`.
     pub rotation: f32,
+    /// `points` split into convex pieces for collision detection.
+    pub convex_parts: Vec<Vec<Point>>,
 }
 
 impl Wall {
     pub fn new(x: f32, y: f32) -> Self {
         let center = Point::new(x, y);
-        let points = create_wall_vertices(&center);
-        let rotation = 0.0;
+        Self::from_points(create_wall_vertices(&center).to_vec(), 0.0)
+    }
+
+    /// `points` should already be checked with `validate_polygon`.
+    /// The outline is rotated by `rotation` (radians) around its center.
+    pub fn from_points(mut points: Vec<Point>, rotation: f32) -> Self {
+        let center = polygon_centroid(&points);
+        if rotation != 0.0 {
+            for point in points.iter_mut() {
+                rotate_around_point(point, &center, rotation);
+            }
+        }
+        let convex_parts = convex_decomposition(&points);
 
         Self {
             points,
             center,
             rotation,
+            convex_parts,
         }
     }
+
+    pub fn bounds(&self) -> Rect {
+        polygon_bounds(&self.points)
+    }
 }
 
 // currently hexagon for collision
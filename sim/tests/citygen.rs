@@ -0,0 +1,192 @@
+//! Generated cities: valid, the same every time for a seed, and possible to
+//! drive around.
+
+use std::collections::{BTreeSet, VecDeque};
+use taxi_sim::citygen::generate_city;
+use taxi_sim::constants::TILE_SIZE;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::{Level, RegionKind};
+use taxi_sim::math::{Point, Rect, polygon_bounds, polygon_collision};
+use taxi_sim::tilemap::{Tile, Tilemap};
+
+const SEEDS: [u64; 5] = [0, 1, 42, 1234, 0xdead_beef];
+
+/// Tiles a car can drive onto: not a building tile, and not touching any
+/// building wall.
+fn is_drivable(level: &Level, tilemap: &Tilemap, tile_x: i32, tile_y: i32) -> bool {
+    if tilemap.get(tile_x, tile_y) == Tile::Building {
+        return false;
+    }
+    let bounds = Tilemap::tile_bounds(tile_x, tile_y);
+    let square = [
+        Point::new(bounds.x, bounds.y),
+        Point::new(bounds.x + bounds.w, bounds.y),
+        Point::new(bounds.x + bounds.w, bounds.y + bounds.h),
+        Point::new(bounds.x, bounds.y + bounds.h),
+    ];
+    !level.walls.iter().any(|wall| {
+        polygon_bounds(&wall.points).overlaps(&bounds)
+            && polygon_collision(&square, &wall.points).is_some()
+    })
+}
+
+/// Every drivable tile you can get to from `from` without leaving `area`.
+fn flood_fill(level: &Level, from: &Point, area: &Rect) -> BTreeSet<(i32, i32)> {
+    let tilemap = level.tiles.to_tilemap().unwrap();
+    let (min_x, min_y) = Tilemap::tile_at(&Point::new(area.x, area.y));
+    let (max_x, max_y) = Tilemap::tile_at(&Point::new(area.x + area.w, area.y + area.h));
+
+    let start = Tilemap::tile_at(from);
+    let mut reached = BTreeSet::from([start]);
+    let mut open = VecDeque::from([start]);
+    while let Some((x, y)) = open.pop_front() {
+        for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            let inside = (min_x..=max_x).contains(&next.0) && (min_y..=max_y).contains(&next.1);
+            if inside && !reached.contains(&next) && is_drivable(level, &tilemap, next.0, next.1) {
+                reached.insert(next);
+                open.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+/// The tiles part of the level, in world space.
+fn city_bounds(level: &Level) -> Rect {
+    let (x, y) = level.tiles.origin;
+    let height = level.tiles.rows.len();
+    let width = level.tiles.rows[0].chars().count();
+    Rect::new(
+        x as f32 * TILE_SIZE,
+        y as f32 * TILE_SIZE,
+        width as f32 * TILE_SIZE,
+        height as f32 * TILE_SIZE,
+    )
+}
+
+#[test]
+fn same_seed_same_city() {
+    let a = generate_city(7);
+    let b = generate_city(7);
+    assert_eq!(a.tiles.rows, b.tiles.rows);
+    assert_eq!(
+        a.walls.iter().map(|wall| &wall.points).collect::<Vec<_>>(),
+        b.walls.iter().map(|wall| &wall.points).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        a.regions
+            .iter()
+            .map(|region| &region.name)
+            .collect::<Vec<_>>(),
+        b.regions
+            .iter()
+            .map(|region| &region.name)
+            .collect::<Vec<_>>()
+    );
+
+    let c = generate_city(8);
+    assert_ne!(a.tiles.rows, c.tiles.rows);
+}
+
+#[test]
+fn cities_are_valid_levels() {
+    for seed in SEEDS {
+        let level = generate_city(seed);
+        level.validate().unwrap();
+
+        // And they survive being written out and read back in.
+        let text = ron::to_string(&level).unwrap();
+        Level::parse(&text).unwrap();
+
+        assert!(!level.walls.is_empty());
+        let pickups = level
+            .regions
+            .iter()
+            .filter(|region| region.kind == RegionKind::Pickup)
+            .count();
+        assert!(pickups >= 2, "seed {} only has {} pickups", seed, pickups);
+        assert!(
+            level
+                .regions
+                .iter()
+                .any(|region| region.kind == RegionKind::Garage)
+        );
+    }
+}
+
+#[test]
+fn spawn_is_on_the_road_and_clear() {
+    for seed in SEEDS {
+        let level = generate_city(seed);
+        let tilemap = level.tiles.to_tilemap().unwrap();
+        let (x, y) = Tilemap::tile_at(&level.spawn.position);
+
+        assert_eq!(tilemap.get(x, y), Tile::Road, "seed {}", seed);
+        let game_state = GameState::from_level(&level);
+        assert!(
+            game_state
+                .walls_in(&game_state.player.bounds())
+                .next()
+                .is_none()
+        );
+    }
+}
+
+#[test]
+fn every_destination_is_reachable_from_spawn() {
+    for seed in SEEDS {
+        let level = generate_city(seed);
+        let reached = flood_fill(&level, &level.spawn.position, &city_bounds(&level));
+        let game_state = GameState::from_level(&level);
+
+        for region in level.regions.iter() {
+            let center = region.area.center();
+            assert!(
+                reached.contains(&Tilemap::tile_at(&center)),
+                "seed {}: can't drive to {}",
+                seed,
+                region.name
+            );
+            // And the directions know the way too.
+            assert!(
+                game_state
+                    .roads
+                    .find_route(&level.spawn.position, &center)
+                    .is_some(),
+                "seed {}: no route to {}",
+                seed,
+                region.name
+            );
+        }
+    }
+}
+
+#[test]
+fn zones_are_on_the_curb() {
+    for seed in SEEDS {
+        let level = generate_city(seed);
+        let tilemap = level.tiles.to_tilemap().unwrap();
+
+        for region in level.regions.iter() {
+            let area = region.area;
+            let corners = [
+                Point::new(area.x, area.y),
+                Point::new(area.x + area.w, area.y + area.h),
+            ];
+            let tiles: Vec<Tile> = corners
+                .iter()
+                .map(|corner| {
+                    let (x, y) = Tilemap::tile_at(corner);
+                    tilemap.get(x, y)
+                })
+                .collect();
+            // One corner's on the street, the other on the sidewalk.
+            assert!(
+                tiles.contains(&Tile::Road) && tiles.contains(&Tile::Sidewalk),
+                "seed {}: {} isn't on a curb",
+                seed,
+                region.name
+            );
+        }
+    }
+}
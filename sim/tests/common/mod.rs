@@ -0,0 +1,57 @@
+//! Helpers shared by the integration tests; add `mod common;` to use them.
+//! Not every test file uses all of them.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use taxi_sim::constants::SIMULATION_TICK_RATE;
+use taxi_sim::events::GameEvent;
+use taxi_sim::gamestate::{GameState, simulate};
+use taxi_sim::input::InputFrame;
+use taxi_sim::player::PlayerAction;
+
+pub const DELTA_TIME: f32 = 1.0 / SIMULATION_TICK_RATE;
+
+/// Simulates `ticks` ticks holding `actions`, like the game does: one shot
+/// actions only go in on the first tick.
+pub fn run(game_state: &mut GameState, actions: &[PlayerAction], ticks: usize) {
+    run_events(game_state, actions, ticks);
+}
+
+/// Same as `run`, returning every event from the ticks it ran.
+pub fn run_events(
+    game_state: &mut GameState,
+    actions: &[PlayerAction],
+    ticks: usize,
+) -> Vec<GameEvent> {
+    let mut input_frame = InputFrame::new();
+    input_frame.player_actions.extend_from_slice(actions);
+    let mut events = Vec::new();
+    for _ in 0..ticks {
+        simulate(&input_frame, game_state, DELTA_TIME);
+        input_frame.remove_one_shot_actions();
+        events.extend(game_state.events.iter().cloned());
+    }
+    events
+}
+
+/// Total of how hard the taxi hit things in `events`.
+pub fn total_impact_speed(events: &[GameEvent]) -> f32 {
+    events
+        .iter()
+        .map(|event| match event {
+            GameEvent::Collision { impact_speed, .. } => *impact_speed,
+            _ => 0.0,
+        })
+        .sum()
+}
+
+/// A file in the temp dir for a test to use, with the process ID in the name
+/// so test runs going at the same time don't trip over each other.
+pub fn temp_path(name: &str, extension: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "taxi-sim-{}-{}.{}",
+        name,
+        std::process::id(),
+        extension
+    ))
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use common::{run, total_impact_speed};
+use taxi_sim::constants::{
+    DAMAGE_MIN_IMPACT_SPEED, DAMAGE_PER_IMPACT_SPEED, MAX_HEALTH, REPAIR_COST, REPAIR_RATE,
+    SIMULATION_TICK_RATE, TOW_COST, TOW_DELAY,
+};
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::Level;
+use taxi_sim::math::Point;
+use taxi_sim::passenger::{Passenger, PassengerState};
+use taxi_sim::player::{Player, PlayerAction};
+
+/// Middle of the garage in `game_with_wall_ahead`.
+const GARAGE: (f32, f32) = (0.0, 300.0);
+
+/// Taxi facing a wall `distance` in front of it, and a garage just behind it
+/// (see `GARAGE`).
+fn game_with_wall_ahead(distance: f32) -> GameState {
+    let text = format!(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            walls: [(points: [
+                (x: -500.0, y: {top}), (x: 500.0, y: {top}),
+                (x: 500.0, y: {bottom}), (x: -500.0, y: {bottom}),
+            ])],
+            regions: [(name: \"garage\", area: (x: -100.0, y: 200.0, w: 200.0, h: 200.0), kind: Garage)],
+        )",
+        top = -distance - 100.0,
+        bottom = -distance,
+    );
+    GameState::from_level(&Level::parse(&text).unwrap())
+}
+
+/// Drives into the wall and returns the hardest impact speed.
+fn crash(game_state: &mut GameState) -> f32 {
+    let mut hardest_impact: f32 = 0.0;
+    for _ in 0..(3 * SIMULATION_TICK_RATE as usize) {
+        run(game_state, &[PlayerAction::GasHeld], 1);
+        hardest_impact = hardest_impact.max(total_impact_speed(&game_state.events));
+    }
+    hardest_impact
+}
+
+#[test]
+fn crashing_costs_health_by_how_hard_it_was() {
+    let mut short_run_up = game_with_wall_ahead(150.0);
+    let mut long_run_up = game_with_wall_ahead(1500.0);
+    let slow_impact = crash(&mut short_run_up);
+    let fast_impact = crash(&mut long_run_up);
+    assert!(fast_impact > slow_impact);
+
+    let slow_damage = MAX_HEALTH - short_run_up.player.health;
+    let fast_damage = MAX_HEALTH - long_run_up.player.health;
+    assert!(slow_damage > 0.0);
+    assert!(fast_damage > slow_damage);
+
+    // Pressing against the wall afterwards doesn't keep doing damage.
+    let expected = (fast_impact - DAMAGE_MIN_IMPACT_SPEED) * DAMAGE_PER_IMPACT_SPEED;
+    assert!(
+        (fast_damage - expected).abs() < 1.0,
+        "expected {} damage, got {}",
+        expected,
+        fast_damage
+    );
+}
+
+#[test]
+fn bumps_dont_do_any_damage() {
+    let mut game_state = game_with_wall_ahead(150.0);
+    // Nose just touching the wall, barely moving.
+    game_state.player.reposition(0.0, -96.0, true);
+    game_state.player.velocity = Point::new(0.0, -60.0);
+    run(&mut game_state, &[], 60);
+
+    assert_eq!(game_state.player.health, MAX_HEALTH);
+}
+
+#[test]
+fn wrecked_taxi_wont_drive() {
+    let mut game_state = game_with_wall_ahead(1500.0);
+    game_state.player.health = 0.0;
+    run(&mut game_state, &[PlayerAction::GasHeld], 120);
+
+    assert!(game_state.player.is_wrecked());
+    assert_eq!(game_state.player.speed(), 0.0);
+}
+
+#[test]
+fn wrecks_get_towed_to_the_garage() {
+    let mut game_state = game_with_wall_ahead(1500.0);
+    game_state.player.health = 0.0;
+    let tow_ticks = (TOW_DELAY * SIMULATION_TICK_RATE as f64) as usize;
+    run(&mut game_state, &[], tow_ticks - 2);
+    assert_eq!(game_state.player.center.y, 0.0);
+
+    run(&mut game_state, &[], 3);
+    assert_eq!(game_state.player.center.x, GARAGE.0);
+    assert_eq!(game_state.player.center.y, GARAGE.1);
+    // And repairs start straight away.
+    assert!(game_state.money < -TOW_COST);
+    assert!(!game_state.player.is_wrecked());
+}
+
+#[test]
+fn wrecks_get_fixed_in_place_without_a_garage() {
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    let wreck_site = game_state.player.center;
+    game_state.player.health = 0.0;
+    let tow_ticks = (TOW_DELAY * SIMULATION_TICK_RATE as f64) as usize;
+    run(&mut game_state, &[], tow_ticks + 1);
+
+    assert_eq!(game_state.player.center, wreck_site);
+    assert_eq!(game_state.player.health, MAX_HEALTH);
+    assert_eq!(game_state.money, -TOW_COST);
+}
+
+#[test]
+fn garages_repair_the_taxi_for_money() {
+    let mut game_state = game_with_wall_ahead(1500.0);
+    game_state.player.health = 0.0;
+    game_state.player.reposition(GARAGE.0, GARAGE.1, true);
+    run(&mut game_state, &[], SIMULATION_TICK_RATE as usize);
+
+    assert!(!game_state.player.is_wrecked());
+    assert!((game_state.player.health - REPAIR_RATE).abs() < 0.01);
+    assert!((game_state.money + REPAIR_RATE * REPAIR_COST).abs() < 0.01);
+
+    // Topped out at full health.
+    run(&mut game_state, &[], 10 * SIMULATION_TICK_RATE as usize);
+    assert_eq!(game_state.player.health, MAX_HEALTH);
+    assert!((game_state.money + MAX_HEALTH * REPAIR_COST).abs() < 0.01);
+}
+
+#[test]
+fn garages_only_repair_when_stopped() {
+    let mut game_state = game_with_wall_ahead(1500.0);
+    game_state.player.health = 50.0;
+    game_state.player.reposition(GARAGE.0, GARAGE.1, true);
+    game_state.player.velocity = Point::new(0.0, -200.0);
+    run(&mut game_state, &[PlayerAction::GasHeld], 10);
+
+    assert_eq!(game_state.player.health, 50.0);
+    assert_eq!(game_state.money, 0.0);
+}
+
+#[test]
+fn passengers_notice_the_damage_done_during_their_ride() {
+    let mut game_state = game_with_wall_ahead(1500.0);
+    game_state.passengers.push(Passenger {
+        pickup: "nowhere".to_owned(),
+        destination: "nowhere".to_owned(),
+        state: PassengerState::Riding { picked_up_at: 0.0 },
+        spawned_at: 0.0,
+        damage: 0.0,
+    });
+    crash(&mut game_state);
+
+    let lost = MAX_HEALTH - game_state.player.health;
+    assert!(lost > 0.0);
+    assert!((game_state.passengers[0].damage - lost).abs() < 0.01);
+}
+
+#[test]
+fn both_cars_are_damaged_in_a_crash_with_traffic() {
+    // Traffic drives away up the lane, the taxi catches up and rams it.
+    let mut game_state = GameState::from_level(
+        &Level::parse(
+            "Level(
+                spawn: (position: (x: 0.0, y: 0.0)),
+                lanes: [(points: [(x: 0.0, y: -300.0), (x: 0.0, y: -5000.0)], cars: 1)],
+            )",
+        )
+        .unwrap(),
+    );
+    run(
+        &mut game_state,
+        &[PlayerAction::GasHeld],
+        3 * SIMULATION_TICK_RATE as usize,
+    );
+
+    assert!(game_state.player.health < MAX_HEALTH);
+    assert!(game_state.traffic[0].car.health < MAX_HEALTH);
+}
@@ -0,0 +1,201 @@
+//! Crazy dash timing, boost, cooldown and chains, on the sim clock.
+
+mod common;
+
+use taxi_sim::constants::{
+    CRAZY_DASH_BOOST_COST, CRAZY_DASH_CHAIN_BONUS, CRAZY_DASH_CHAIN_WINDOW, SIMULATION_TICK_RATE,
+};
+use taxi_sim::events::GameEvent;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::player::{DashDenied, DashTiming, Player, PlayerAction};
+
+/// Ticks between shifting into drive and the gas for each timing.
+const PERFECT_TICKS: usize = 6;
+const GOOD_TICKS: usize = 10;
+const LATE_TICKS: usize = 20;
+
+/// Long enough for a dash to end and cool down.
+const BETWEEN_DASHES_TICKS: usize = 80;
+
+fn new_game() -> GameState {
+    GameState::new(Player::new(0.0, 0.0))
+}
+
+/// Returns every dash event from the ticks it ran.
+fn run(game_state: &mut GameState, actions: &[PlayerAction], ticks: usize) -> Vec<GameEvent> {
+    common::run_events(game_state, actions, ticks)
+        .into_iter()
+        .filter(|event| {
+            matches!(
+                event,
+                GameEvent::DashStarted { .. } | GameEvent::DashEnded | GameEvent::DashDenied(_)
+            )
+        })
+        .collect()
+}
+
+/// Neutral, drive, then the gas `ticks` after drive. Returns the events from
+/// the tick the gas was hit.
+fn try_dash(game_state: &mut GameState, ticks: usize) -> Vec<GameEvent> {
+    run(game_state, &[PlayerAction::ShiftIntoNeutral], 1);
+    run(game_state, &[PlayerAction::ShiftIntoDrive], 1);
+    run(game_state, &[], ticks - 1);
+    run(
+        game_state,
+        &[PlayerAction::GasActivated, PlayerAction::GasHeld],
+        1,
+    )
+}
+
+fn started(timing: DashTiming, chain: u32) -> GameEvent {
+    GameEvent::DashStarted {
+        timing,
+        chain,
+        strength: 1.0 + CRAZY_DASH_CHAIN_BONUS * (chain - 1) as f32,
+    }
+}
+
+#[test]
+fn perfect_and_good_timing_are_told_apart() {
+    let mut game_state = new_game();
+    assert_eq!(
+        try_dash(&mut game_state, PERFECT_TICKS),
+        vec![started(DashTiming::Perfect, 1)]
+    );
+
+    let mut game_state = new_game();
+    assert_eq!(
+        try_dash(&mut game_state, GOOD_TICKS),
+        vec![started(DashTiming::Good, 1)]
+    );
+
+    let mut game_state = new_game();
+    assert_eq!(try_dash(&mut game_state, LATE_TICKS), vec![]);
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn dash_ends_on_its_own() {
+    let mut game_state = new_game();
+    try_dash(&mut game_state, PERFECT_TICKS);
+    assert!(game_state.player.is_crazy_dashing);
+
+    let events = run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    assert_eq!(events, vec![GameEvent::DashEnded]);
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn dashing_uses_boost_which_refills() {
+    let mut game_state = new_game();
+    try_dash(&mut game_state, PERFECT_TICKS);
+    let boost = game_state.player.boost;
+    assert!(boost < 1.0 - CRAZY_DASH_BOOST_COST + 0.01);
+
+    run(&mut game_state, &[], 120);
+    assert!(game_state.player.boost > boost);
+
+    run(&mut game_state, &[], 120 * 10);
+    assert_eq!(game_state.player.boost, 1.0);
+}
+
+#[test]
+fn no_dash_without_enough_boost() {
+    let mut game_state = new_game();
+    game_state.player.boost = CRAZY_DASH_BOOST_COST / 2.0;
+
+    assert_eq!(
+        try_dash(&mut game_state, PERFECT_TICKS),
+        vec![GameEvent::DashDenied(DashDenied::NoBoost)]
+    );
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn no_dash_while_cooling_down() {
+    let mut game_state = new_game();
+    try_dash(&mut game_state, PERFECT_TICKS);
+    // Dash is 0.3 seconds, so this is just after it ends.
+    let events = run(&mut game_state, &[PlayerAction::GasHeld], 40);
+    assert_eq!(events, vec![GameEvent::DashEnded]);
+
+    assert_eq!(
+        try_dash(&mut game_state, PERFECT_TICKS),
+        vec![GameEvent::DashDenied(DashDenied::Cooldown)]
+    );
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn perfect_dashes_chain_and_get_stronger() {
+    let mut game_state = new_game();
+    try_dash(&mut game_state, PERFECT_TICKS);
+    run(&mut game_state, &[], BETWEEN_DASHES_TICKS);
+
+    assert_eq!(
+        try_dash(&mut game_state, PERFECT_TICKS),
+        vec![started(DashTiming::Perfect, 2)]
+    );
+    assert_eq!(game_state.player.crazy_dash_chain, 2);
+    assert!(game_state.player.crazy_dash_strength > 1.0);
+
+    // A good dash starts over.
+    run(&mut game_state, &[], BETWEEN_DASHES_TICKS);
+    assert_eq!(
+        try_dash(&mut game_state, GOOD_TICKS),
+        vec![started(DashTiming::Good, 1)]
+    );
+}
+
+#[test]
+fn chain_stops_at_the_max() {
+    let mut game_state = new_game();
+    game_state.player.tuning.crazy_dash_max_chain = 2;
+    game_state.player.tuning.crazy_dash_boost_cost = 0.0;
+
+    let mut chains = Vec::new();
+    for _ in 0..3 {
+        for event in try_dash(&mut game_state, PERFECT_TICKS) {
+            if let GameEvent::DashStarted { chain, .. } = event {
+                chains.push(chain);
+            }
+        }
+        run(&mut game_state, &[], BETWEEN_DASHES_TICKS);
+    }
+
+    assert_eq!(chains, vec![1, 2, 2]);
+}
+
+#[test]
+fn chain_runs_out_if_the_next_dash_is_too_late() {
+    let mut game_state = new_game();
+    try_dash(&mut game_state, PERFECT_TICKS);
+    let window_ticks = (CRAZY_DASH_CHAIN_WINDOW * SIMULATION_TICK_RATE as f64) as usize;
+    run(&mut game_state, &[], window_ticks + BETWEEN_DASHES_TICKS);
+    assert_eq!(game_state.player.crazy_dash_chain, 0);
+
+    assert_eq!(
+        try_dash(&mut game_state, PERFECT_TICKS),
+        vec![started(DashTiming::Perfect, 1)]
+    );
+}
+
+#[test]
+fn chained_dashes_go_faster() {
+    let top_speed = |chain_bonus: f32| {
+        let mut game_state = new_game();
+        game_state.player.tuning.crazy_dash_chain_bonus = chain_bonus;
+        try_dash(&mut game_state, PERFECT_TICKS);
+        run(&mut game_state, &[], BETWEEN_DASHES_TICKS);
+        try_dash(&mut game_state, PERFECT_TICKS);
+
+        let mut top_speed: f32 = 0.0;
+        for _ in 0..30 {
+            run(&mut game_state, &[PlayerAction::GasHeld], 1);
+            top_speed = top_speed.max(game_state.player.speed());
+        }
+        top_speed
+    };
+
+    assert!(top_speed(0.5) > top_speed(0.0) + 100.0);
+}
@@ -0,0 +1,171 @@
+//! What ends up in `GameState::events`, and adding them up into `Stats`.
+
+mod common;
+
+use common::{run, run_events};
+use taxi_sim::constants::SIMULATION_TICK_RATE;
+use taxi_sim::events::{GameEvent, Obstacle};
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::Level;
+use taxi_sim::player::{Player, PlayerAction, ShiftMode};
+use taxi_sim::stats::Stats;
+
+#[test]
+fn events_only_last_one_tick() {
+    // Note `GameState::new` puts a test wall 300px below the player.
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    run(&mut game_state, &[PlayerAction::ShiftIntoNeutral], 1);
+    assert_eq!(game_state.events.len(), 1);
+
+    run(&mut game_state, &[], 1);
+    assert!(game_state.events.is_empty());
+}
+
+#[test]
+fn gear_changes_are_reported_once() {
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    let events = run_events(&mut game_state, &[PlayerAction::ShiftIntoReverse], 10);
+    assert_eq!(
+        events,
+        vec![GameEvent::GearChanged {
+            from: ShiftMode::DRIVE,
+            to: ShiftMode::REVERSE,
+        }]
+    );
+
+    let events = run_events(&mut game_state, &[PlayerAction::ShiftIntoDrive], 10);
+    assert_eq!(
+        events,
+        vec![GameEvent::GearChanged {
+            from: ShiftMode::REVERSE,
+            to: ShiftMode::DRIVE,
+        }]
+    );
+}
+
+#[test]
+fn hitting_a_wall_is_a_collision_pointing_back_at_the_taxi() {
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    let events = run_events(
+        &mut game_state,
+        &[PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld],
+        240,
+    );
+
+    let collisions: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::Collision {
+                normal,
+                impact_speed,
+                with,
+            } => Some((*normal, *impact_speed, *with)),
+            _ => None,
+        })
+        .collect();
+    assert!(!collisions.is_empty());
+    for (normal, impact_speed, with) in collisions {
+        // Wall's below the taxi.
+        assert!(normal.y < -0.9);
+        assert!(impact_speed > 0.0);
+        assert_eq!(with, Obstacle::Wall);
+    }
+}
+
+#[test]
+fn hitting_traffic_says_which_car() {
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            lanes: [(points: [(x: 0.0, y: -300.0), (x: 0.0, y: -5000.0)], cars: 1)],
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+
+    let events = run_events(
+        &mut game_state,
+        &[PlayerAction::GasHeld],
+        3 * SIMULATION_TICK_RATE as usize,
+    );
+
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::Collision {
+            with: Obstacle::Traffic(0),
+            ..
+        }
+    )));
+}
+
+#[test]
+fn rides_are_reported() {
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            regions: [
+                (name: \"a\", area: (x: -100.0, y: -100.0, w: 200.0, h: 200.0), kind: Pickup),
+                (name: \"b\", area: (x: 900.0, y: -100.0, w: 200.0, h: 200.0), kind: Pickup),
+            ],
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+
+    let mut events = run_events(&mut game_state, &[], 2);
+    let pickup = game_state.passengers[0].pickup.clone();
+    let destination = game_state.passengers[0].destination.clone();
+    // Spawned in "a", so that's where it starts if it's waiting there.
+    if pickup == "b" {
+        let pickup = game_state.region("b").unwrap().area.center();
+        let teleport = PlayerAction::Reposition {
+            x: pickup.x,
+            y: pickup.y,
+            reset_motion: true,
+        };
+        events.extend(run_events(&mut game_state, &[teleport], 1));
+    }
+    let destination_center = game_state.region(&destination).unwrap().area.center();
+    let teleport = PlayerAction::Reposition {
+        x: destination_center.x,
+        y: destination_center.y,
+        reset_motion: true,
+    };
+    events.extend(run_events(&mut game_state, &[teleport], 1));
+
+    let mut rides = events.iter().filter(|event| {
+        matches!(
+            event,
+            GameEvent::PassengerPickedUp { .. } | GameEvent::PassengerDroppedOff(_)
+        )
+    });
+    assert_eq!(
+        rides.next(),
+        Some(&GameEvent::PassengerPickedUp {
+            pickup,
+            destination
+        })
+    );
+    assert_eq!(
+        rides.next(),
+        Some(&GameEvent::PassengerDroppedOff(game_state.fares[0].clone()))
+    );
+    assert_eq!(rides.next(), None);
+}
+
+#[test]
+fn stats_add_up_events() {
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    let mut stats = Stats::new();
+    let mut events = run_events(&mut game_state, &[PlayerAction::ShiftIntoReverse], 1);
+    events.extend(run_events(&mut game_state, &[PlayerAction::GasHeld], 240));
+    for event in events.iter() {
+        stats.record(event);
+    }
+
+    assert_eq!(stats.gear_changes, 1);
+    assert!(stats.collisions >= 1);
+    assert!(stats.hardest_hit > 100.0);
+    assert_eq!(stats.dashes, 0);
+    assert_eq!(stats.pickups, 0);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::DELTA_TIME;
+use taxi_sim::gamestate::{GameState, simulate};
+use taxi_sim::input::InputFrame;
+use taxi_sim::level::{Level, LevelError};
+use taxi_sim::math::PolygonError;
+use taxi_sim::player::PlayerAction;
+
+fn level_with_wall(points: &str) -> String {
+    format!(
+        "Level(spawn: (position: (x: 0.0, y: 0.0)), walls: [(points: [{}])])",
+        points
+    )
+}
+
+fn wall_error(text: &str) -> PolygonError {
+    match Level::parse(text) {
+        Err(LevelError::InvalidWall { error, .. }) => error,
+        other => panic!("expected an invalid wall, got {:?}", other),
+    }
+}
+
+#[test]
+fn bundled_test_level_loads() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../levels/test.ron");
+    let level = Level::load(path).unwrap();
+    let game_state = GameState::from_level(&level);
+
+    assert_eq!(game_state.walls().len(), 3);
+    assert_eq!(game_state.traffic.len(), 3);
+    assert!(game_state.region("corner").is_some());
+    assert!(game_state.region("nowhere").is_none());
+}
+
+#[test]
+fn spawn_heading_turns_the_taxi() {
+    let level =
+        Level::parse("Level(spawn: (position: (x: 10.0, y: 20.0), heading: 90.0))").unwrap();
+    let game_state = GameState::from_level(&level);
+
+    assert_eq!(game_state.player.center.x, 10.0);
+    assert_eq!(game_state.player.center.y, 20.0);
+    // 90 degrees clockwise from up is facing right.
+    assert!((game_state.player.forward_normal.x - 1.0).abs() < 1e-5);
+    assert!(game_state.player.forward_normal.y.abs() < 1e-5);
+    assert!(game_state.walls().is_empty());
+}
+
+#[test]
+fn wall_rotation_is_applied_around_its_center() {
+    let level = Level::parse(
+        "Level(spawn: (position: (x: 0.0, y: 0.0)), walls: [(
+            points: [(x: -10.0, y: -5.0), (x: 10.0, y: -5.0), (x: 10.0, y: 5.0), (x: -10.0, y: 5.0)],
+            rotation: 90.0,
+        )])",
+    )
+    .unwrap();
+    let game_state = GameState::from_level(&level);
+    let bounds = game_state.walls()[0].bounds();
+
+    assert!((bounds.w - 10.0).abs() < 1e-4);
+    assert!((bounds.h - 20.0).abs() < 1e-4);
+}
+
+#[test]
+fn malformed_polygons_are_rejected() {
+    assert_eq!(
+        wall_error(&level_with_wall("(x: 0.0, y: 0.0), (x: 1.0, y: 0.0)")),
+        PolygonError::TooFewPoints(2)
+    );
+    assert_eq!(
+        wall_error(&level_with_wall(
+            "(x: 0.0, y: 0.0), (x: 1.0, y: 1.0), (x: 2.0, y: 2.0)"
+        )),
+        PolygonError::ZeroArea
+    );
+    assert_eq!(
+        wall_error(&level_with_wall(
+            "(x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 0.0), (x: 0.0, y: 1.0)"
+        )),
+        PolygonError::DuplicatePoint { index: 2 }
+    );
+    // Bow tie
+    assert_eq!(
+        wall_error(&level_with_wall(
+            "(x: 0.0, y: 0.0), (x: 10.0, y: 10.0), (x: 10.0, y: 0.0), (x: 0.0, y: 10.0)"
+        )),
+        PolygonError::SelfIntersecting {
+            edge_a: 0,
+            edge_b: 2
+        }
+    );
+}
+
+#[test]
+fn bad_regions_are_rejected() {
+    let text = "Level(
+        spawn: (position: (x: 0.0, y: 0.0)),
+        regions: [
+            (name: \"a\", area: (x: 0.0, y: 0.0, w: 10.0, h: 10.0)),
+            (name: \"a\", area: (x: 20.0, y: 0.0, w: 10.0, h: 10.0)),
+        ],
+    )";
+    assert!(matches!(
+        Level::parse(text),
+        Err(LevelError::InvalidRegion { .. })
+    ));
+}
+
+#[test]
+fn bad_lanes_are_rejected() {
+    let lane_error = |lane: &str| {
+        let text = format!(
+            "Level(spawn: (position: (x: 0.0, y: 0.0)), lanes: [{}])",
+            lane
+        );
+        match Level::parse(&text) {
+            Err(LevelError::InvalidLane { index: 0, reason }) => reason,
+            other => panic!("expected an invalid lane, got {:?}", other),
+        }
+    };
+
+    assert_eq!(
+        lane_error("(points: [(x: 0.0, y: 0.0)])"),
+        "needs at least 2 points"
+    );
+    assert_eq!(
+        lane_error("(points: [(x: 0.0, y: 0.0), (x: 0.0, y: 500.0)], cars: 3)"),
+        "has more cars than points"
+    );
+}
+
+#[test]
+fn syntax_errors_are_parse_errors() {
+    assert!(matches!(
+        Level::parse("Level(spawn: "),
+        Err(LevelError::Parse(_))
+    ));
+}
+
+#[test]
+fn concave_walls_block_the_taxi_inside_the_notch() {
+    // U shape opening downwards, taxi drives up into the notch.
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            walls: [(points: [
+                (x: -200.0, y: -400.0), (x: 200.0, y: -400.0), (x: 200.0, y: -100.0),
+                (x: 100.0, y: -100.0), (x: 100.0, y: -300.0), (x: -100.0, y: -300.0),
+                (x: -100.0, y: -100.0), (x: -200.0, y: -100.0),
+            ])],
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+    assert!(game_state.walls()[0].convex_parts.len() > 1);
+
+    let mut input_frame = InputFrame::new();
+    input_frame.player_actions.push(PlayerAction::GasHeld);
+    for _ in 0..240 {
+        simulate(&input_frame, &mut game_state, DELTA_TIME);
+    }
+
+    // Made it into the notch, but not through the back of it.
+    let top_of_taxi = game_state.player.bounds().y;
+    assert!(top_of_taxi < -100.0);
+    assert!(top_of_taxi >= -301.0);
+    assert_eq!(game_state.player.center.x, 0.0);
+}
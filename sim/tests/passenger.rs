@@ -0,0 +1,107 @@
+mod common;
+
+use common::run;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::Level;
+use taxi_sim::math::Point;
+use taxi_sim::passenger::{PassengerState, calculate_fare};
+use taxi_sim::player::PlayerAction;
+
+/// Two pickup spots far apart, nothing in the way.
+fn two_stop_game() -> GameState {
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            regions: [
+                (name: \"a\", area: (x: -100.0, y: -100.0, w: 200.0, h: 200.0), kind: Pickup),
+                (name: \"b\", area: (x: 900.0, y: -100.0, w: 200.0, h: 200.0), kind: Pickup),
+                (name: \"park\", area: (x: 400.0, y: 400.0, w: 200.0, h: 200.0)),
+            ],
+        )",
+    )
+    .unwrap();
+    GameState::from_level(&level)
+}
+
+fn teleport_to(game_state: &mut GameState, region_name: &str) {
+    let center = game_state.region(region_name).unwrap().area.center();
+    run(
+        game_state,
+        &[PlayerAction::Reposition {
+            x: center.x,
+            y: center.y,
+            reset_motion: false,
+        }],
+        1,
+    );
+}
+
+#[test]
+fn passengers_only_wait_at_pickup_regions() {
+    let mut game_state = two_stop_game();
+    run(&mut game_state, &[], 1);
+
+    assert_eq!(game_state.passengers.len(), 1);
+    let passenger = &game_state.passengers[0];
+    assert_ne!(passenger.pickup, "park");
+    assert_ne!(passenger.destination, "park");
+    assert_ne!(passenger.pickup, passenger.destination);
+}
+
+#[test]
+fn full_ride_pays_a_fare() {
+    let mut game_state = two_stop_game();
+    run(&mut game_state, &[], 1);
+    let pickup = game_state.passengers[0].pickup.clone();
+    let destination = game_state.passengers[0].destination.clone();
+
+    teleport_to(&mut game_state, &pickup);
+    run(&mut game_state, &[], 1);
+    assert!(matches!(
+        game_state.passengers[0].state,
+        PassengerState::Riding { .. }
+    ));
+
+    run(&mut game_state, &[], 120);
+    teleport_to(&mut game_state, &destination);
+    run(&mut game_state, &[], 1);
+
+    assert!(game_state.riding_passenger().is_none());
+    assert_eq!(game_state.fares.len(), 1);
+    assert!(game_state.money > 0.0);
+    assert_eq!(game_state.money, game_state.fares[0].amount);
+    assert!((game_state.fares[0].ride_time - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn driving_through_a_zone_doesnt_pick_up() {
+    let mut game_state = two_stop_game();
+    run(&mut game_state, &[], 1);
+    let pickup = game_state.passengers[0].pickup.clone();
+
+    // Still rolling while inside the zone.
+    game_state.player.velocity = Point::new(0.0, -200.0);
+    teleport_to(&mut game_state, &pickup);
+
+    assert_eq!(game_state.passengers[0].state, PassengerState::Waiting);
+}
+
+#[test]
+fn no_passengers_without_pickup_regions() {
+    let mut game_state =
+        GameState::from_level(&Level::parse("Level(spawn: (position: (x: 0.0, y: 0.0)))").unwrap());
+    run(&mut game_state, &[], 1200);
+
+    assert!(game_state.passengers.is_empty());
+}
+
+#[test]
+fn slow_or_rough_rides_pay_less() {
+    let quick = calculate_fare(1000.0, 5.0, 0.0);
+    let slow = calculate_fare(1000.0, 30.0, 0.0);
+    let crashed = calculate_fare(1000.0, 5.0, 500.0);
+
+    assert!(quick > slow);
+    assert!(quick > crashed);
+    assert_eq!(calculate_fare(1000.0, 5.0, 1_000_000.0), 0.0);
+}
@@ -0,0 +1,163 @@
+//! The broadphase every collision goes through, checked against just testing
+//! every item.
+
+use taxi_sim::math::Rect;
+use taxi_sim::quadtree::Quadtree;
+use taxi_sim::rng::Rng;
+
+fn tree() -> Quadtree<usize> {
+    Quadtree::new(Rect::new(-1000.0, -1000.0, 2000.0, 2000.0))
+}
+
+fn sorted(mut items: Vec<usize>) -> Vec<usize> {
+    items.sort_unstable();
+    items
+}
+
+/// Lots of small boxes, some of them poking out of the tree's bounds.
+fn random_boxes(seed: u64, count: usize) -> Vec<Rect> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| {
+            Rect::new(
+                rng.range_f32(-1100.0, 1000.0),
+                rng.range_f32(-1100.0, 1000.0),
+                rng.range_f32(1.0, 150.0),
+                rng.range_f32(1.0, 150.0),
+            )
+        })
+        .collect()
+}
+
+fn brute_force(boxes: &[Option<Rect>], area: &Rect) -> Vec<usize> {
+    boxes
+        .iter()
+        .enumerate()
+        .filter(|(_, bounds)| bounds.is_some_and(|bounds| bounds.overlaps(area)))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn insert_and_query() {
+    let mut quadtree = tree();
+    quadtree.insert(0, Rect::new(0.0, 0.0, 10.0, 10.0));
+    quadtree.insert(1, Rect::new(500.0, 500.0, 10.0, 10.0));
+
+    assert_eq!(quadtree.len(), 2);
+    assert_eq!(quadtree.query(&Rect::new(5.0, 5.0, 1.0, 1.0)), vec![0]);
+    assert!(
+        quadtree
+            .query(&Rect::new(-500.0, -500.0, 1.0, 1.0))
+            .is_empty()
+    );
+    assert_eq!(
+        sorted(quadtree.query(&Rect::new(0.0, 0.0, 600.0, 600.0))),
+        vec![0, 1]
+    );
+}
+
+#[test]
+fn items_outside_the_bounds_are_still_found() {
+    let mut quadtree = tree();
+    quadtree.insert(0, Rect::new(5000.0, 5000.0, 10.0, 10.0));
+
+    assert_eq!(
+        quadtree.query(&Rect::new(5005.0, 5005.0, 1.0, 1.0)),
+        vec![0]
+    );
+}
+
+#[test]
+fn items_on_node_boundaries_are_found_from_either_side() {
+    let mut quadtree = tree();
+    // Plenty to make it split, all straddling the lines the root splits
+    // along (x = 0 and y = 0) or sitting right on them.
+    for index in 0..20 {
+        let offset = index as f32 * 40.0 - 400.0;
+        quadtree.insert(index, Rect::new(-5.0, offset, 10.0, 10.0));
+    }
+    quadtree.insert(20, Rect::new(0.0, 0.0, 0.0, 0.0));
+    quadtree.insert(21, Rect::new(100.0, -10.0, 10.0, 10.0));
+
+    // Only touching the straddling box's edge from the left, then the right.
+    assert!(
+        quadtree
+            .query(&Rect::new(-20.0, -400.0, 15.0, 1.0))
+            .contains(&0)
+    );
+    assert!(
+        quadtree
+            .query(&Rect::new(5.0, -400.0, 15.0, 1.0))
+            .contains(&0)
+    );
+    // The zero sized one, from all four children.
+    for (x, y) in [(-1.0, -1.0), (0.0, -1.0), (-1.0, 0.0), (0.0, 0.0)] {
+        assert!(quadtree.query(&Rect::new(x, y, 1.0, 1.0)).contains(&20));
+    }
+    // Sitting on top of y = 0, found from below it.
+    assert_eq!(quadtree.query(&Rect::new(100.0, 0.0, 5.0, 5.0)), vec![21]);
+}
+
+#[test]
+fn remove_takes_out_only_that_item() {
+    let mut quadtree = tree();
+    let boxes = random_boxes(1, 50);
+    for (index, bounds) in boxes.iter().enumerate() {
+        quadtree.insert(index, *bounds);
+    }
+
+    assert!(quadtree.remove(7, &boxes[7]));
+    assert_eq!(quadtree.len(), 49);
+    assert!(!quadtree.query(&boxes[7]).contains(&7));
+    // Already gone.
+    assert!(!quadtree.remove(7, &boxes[7]));
+    assert_eq!(quadtree.len(), 49);
+
+    let everything = Rect::new(-2000.0, -2000.0, 4000.0, 4000.0);
+    assert_eq!(quadtree.query(&everything).len(), 49);
+}
+
+#[test]
+fn clear_empties_it() {
+    let mut quadtree = tree();
+    for (index, bounds) in random_boxes(2, 50).into_iter().enumerate() {
+        quadtree.insert(index, bounds);
+    }
+    quadtree.clear();
+
+    assert!(quadtree.is_empty());
+    let everything = Rect::new(-2000.0, -2000.0, 4000.0, 4000.0);
+    assert!(quadtree.query(&everything).is_empty());
+
+    // And still works afterwards.
+    quadtree.insert(0, Rect::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(quadtree.query(&everything), vec![0]);
+}
+
+#[test]
+fn queries_match_brute_force() {
+    for seed in [3, 4, 5] {
+        let mut quadtree = tree();
+        let mut boxes: Vec<Option<Rect>> = random_boxes(seed, 500).into_iter().map(Some).collect();
+        for (index, bounds) in boxes.iter().enumerate() {
+            quadtree.insert(index, bounds.unwrap());
+        }
+        // Take some out again, so removal is checked too.
+        for index in (0..boxes.len()).step_by(3) {
+            assert!(quadtree.remove(index, &boxes[index].unwrap()));
+            boxes[index] = None;
+        }
+
+        for area in random_boxes(seed + 100, 200) {
+            let area = Rect::new(area.x, area.y, area.w * 3.0, area.h * 3.0);
+            assert_eq!(
+                sorted(quadtree.query(&area)),
+                brute_force(&boxes, &area),
+                "seed {}, area {:?}",
+                seed,
+                area
+            );
+        }
+    }
+}
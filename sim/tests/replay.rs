@@ -0,0 +1,108 @@
+mod common;
+
+use common::{DELTA_TIME, temp_path};
+use std::path::PathBuf;
+use taxi_sim::gamestate::{GameState, simulate};
+use taxi_sim::input::InputFrame;
+use taxi_sim::player::{Player, PlayerAction};
+use taxi_sim::replay::{Replay, ReplayError, ReplayHeader, ReplayRecorder};
+use taxi_sim::tuning::Tuning;
+
+/// Plays a short crazy dash into a turn and records it, with some
+/// non-default tuning so replays have to use the recorded tuning to match.
+fn record_run(path: &PathBuf) -> GameState {
+    let mut game_state = GameState::new(Player::new(0.0, 0.0));
+    game_state.player.set_tuning(Tuning {
+        gas_velocity: 1500.0,
+        tire_grip: 600.0,
+        ..Tuning::default()
+    });
+    let header = ReplayHeader {
+        spawn: game_state.player.center,
+        level: None,
+        seed: None,
+        tuning: game_state.player.tuning.clone(),
+    };
+    let mut recorder = ReplayRecorder::create(path, &header).unwrap();
+
+    let script = [
+        (
+            vec![PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld],
+            20,
+        ),
+        (vec![PlayerAction::ShiftIntoDrive], 6),
+        (vec![PlayerAction::GasActivated, PlayerAction::GasHeld], 1),
+        (vec![PlayerAction::GasHeld, PlayerAction::Steer(-1.0)], 90),
+        (
+            vec![PlayerAction::Reposition {
+                x: 50.0,
+                y: 75.0,
+                reset_motion: false,
+            }],
+            1,
+        ),
+        (vec![PlayerAction::GasHeld], 30),
+    ];
+
+    for (actions, ticks) in script {
+        let mut input_frame = InputFrame::new();
+        input_frame.player_actions = actions;
+        for _ in 0..ticks {
+            simulate(&input_frame, &mut game_state, DELTA_TIME);
+            recorder
+                .record(&input_frame, DELTA_TIME, &game_state)
+                .unwrap();
+            input_frame.remove_one_shot_actions();
+        }
+    }
+
+    game_state
+}
+
+#[test]
+fn recorded_run_replays_exactly() {
+    let path = temp_path("exact", "jsonl");
+    let recorded = record_run(&path);
+
+    let replay = Replay::load(&path).unwrap();
+    assert_eq!(replay.ticks.len(), 148);
+    assert_eq!(
+        replay.ticks[26].player_actions,
+        vec![PlayerAction::GasActivated, PlayerAction::GasHeld]
+    );
+
+    let game_state = replay.new_game_state().unwrap();
+    let replayed = replay.verify(game_state).unwrap();
+    assert_eq!(replayed.checksum(), recorded.checksum());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn tampered_replay_reports_the_tick_it_diverged_on() {
+    let path = temp_path("tampered", "jsonl");
+    record_run(&path);
+
+    let mut replay = Replay::load(&path).unwrap();
+    replay.ticks[40].player_actions.clear();
+
+    match replay.verify(replay.new_game_state().unwrap()) {
+        Err(mismatch) => assert_eq!(mismatch.tick, 40),
+        Ok(_) => panic!("tampered replay still verified"),
+    }
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn garbage_replay_is_a_parse_error() {
+    let path = temp_path("garbage", "jsonl");
+    std::fs::write(&path, "{\"spawn\":{\"x\":0.0,\"y\":0.0}}\nnot json\n").unwrap();
+
+    match Replay::load(&path) {
+        Err(ReplayError::Parse { line, .. }) => assert_eq!(line, 2),
+        other => panic!("expected parse error, got {:?}", other.err()),
+    }
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,186 @@
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::{Level, LevelError};
+use taxi_sim::math::Point;
+use taxi_sim::passenger::{Passenger, PassengerState};
+use taxi_sim::roads::RoadGraph;
+
+/// A square with a long way and a short way round from one corner to the
+/// opposite one, plus a road off on its own.
+///
+/// ```text
+/// 0 ---- 1
+/// |      |
+/// |      |
+/// 3 ---- 2      4 -- 5
+/// ```
+fn square_roads() -> RoadGraph {
+    ron::from_str(
+        "(
+            nodes: [
+                (x: 0.0, y: 0.0), (x: 1000.0, y: 0.0), (x: 1000.0, y: 1000.0),
+                (x: 0.0, y: 1000.0), (x: 3000.0, y: 1000.0), (x: 4000.0, y: 1000.0),
+            ],
+            edges: [
+                (from: 0, to: 1), (from: 1, to: 2), (from: 2, to: 3), (from: 3, to: 0),
+                (from: 4, to: 5),
+            ],
+        )",
+    )
+    .unwrap()
+}
+
+fn assert_near(actual: &Point, expected: (f32, f32)) {
+    assert!(
+        (actual.x - expected.0).abs() < 0.01 && (actual.y - expected.1).abs() < 0.01,
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn route_follows_the_roads_through_every_turn() {
+    let roads = square_roads();
+    // Just off the top road near 0, to just off the right road near 2.
+    let route = roads
+        .find_route(&Point::new(100.0, -20.0), &Point::new(1030.0, 900.0))
+        .unwrap();
+
+    let expected = [
+        (100.0, -20.0),
+        (100.0, 0.0),
+        (1000.0, 0.0),
+        (1000.0, 900.0),
+        (1030.0, 900.0),
+    ];
+    assert_eq!(route.points.len(), expected.len());
+    for (point, expected) in route.points.iter().zip(expected) {
+        assert_near(point, expected);
+    }
+    assert!((route.length - (20.0 + 900.0 + 900.0 + 30.0)).abs() < 0.1);
+}
+
+#[test]
+fn route_takes_the_short_way_round() {
+    let roads = square_roads();
+    // Near 3 to near 1: both ways are the same length except for where the
+    // ends join, so go from the left road near 0.
+    let route = roads
+        .find_route(&Point::new(0.0, 100.0), &Point::new(900.0, 0.0))
+        .unwrap();
+
+    // Up through 0 rather than down and round through 3 and 2.
+    assert_eq!(route.points.len(), 3);
+    assert_near(&route.points[1], (0.0, 0.0));
+    assert!((route.length - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn route_along_a_single_road_goes_straight_there() {
+    let route = square_roads()
+        .find_route(&Point::new(200.0, 0.0), &Point::new(800.0, 0.0))
+        .unwrap();
+
+    assert_eq!(route.points.len(), 2);
+    assert!((route.length - 600.0).abs() < 0.01);
+}
+
+#[test]
+fn no_route_between_roads_that_dont_connect() {
+    let roads = square_roads();
+    assert!(
+        roads
+            .find_route(&Point::new(0.0, 0.0), &Point::new(3500.0, 1000.0))
+            .is_none()
+    );
+    assert!(
+        RoadGraph::default()
+            .find_route(&Point::new(0.0, 0.0), &Point::new(1.0, 1.0))
+            .is_none()
+    );
+}
+
+#[test]
+fn next_turn_skips_points_already_reached() {
+    let route = square_roads()
+        .find_route(&Point::new(100.0, 0.0), &Point::new(1000.0, 900.0))
+        .unwrap();
+
+    let taxi = Point::new(990.0, 5.0);
+    assert_near(&route.next_turn(&taxi, 50.0).unwrap(), (1000.0, 900.0));
+    // Nearly there, nowhere left to turn.
+    assert!(route.next_turn(&Point::new(1000.0, 880.0), 50.0).is_none());
+}
+
+#[test]
+fn bad_roads_are_rejected() {
+    let road_error = |edges: &str| {
+        let text = format!(
+            "Level(
+                spawn: (position: (x: 0.0, y: 0.0)),
+                roads: (nodes: [(x: 0.0, y: 0.0), (x: 100.0, y: 0.0)], edges: [{}]),
+            )",
+            edges
+        );
+        match Level::parse(&text) {
+            Err(LevelError::InvalidRoad { index: 0, reason }) => reason,
+            other => panic!("expected an invalid road, got {:?}", other),
+        }
+    };
+
+    assert_eq!(
+        road_error("(from: 0, to: 2)"),
+        "goes to a node that doesn't exist"
+    );
+    assert_eq!(road_error("(from: 1, to: 1)"), "goes from a node to itself");
+    assert_eq!(
+        road_error("(from: 0, to: 1, lanes: 0)"),
+        "needs at least one lane with a positive width"
+    );
+}
+
+#[test]
+fn every_pickup_in_the_bundled_level_is_reachable_by_road() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../levels/test.ron");
+    let game_state = GameState::from_level(&Level::load(path).unwrap());
+    let spawn = game_state.player.center;
+
+    for region in game_state.regions.iter() {
+        let route = game_state.roads.find_route(&spawn, &region.area.center());
+        assert!(route.is_some(), "can't get to {}", region.name);
+    }
+}
+
+#[test]
+fn destination_is_the_ride_or_the_closest_pickup() {
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            regions: [
+                (name: \"near\", area: (x: 100.0, y: 0.0, w: 100.0, h: 100.0), kind: Pickup),
+                (name: \"far\", area: (x: 900.0, y: 0.0, w: 100.0, h: 100.0), kind: Pickup),
+            ],
+            roads: (nodes: [(x: 0.0, y: 50.0), (x: 950.0, y: 50.0)], edges: [(from: 0, to: 1)]),
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+    assert!(game_state.destination().is_none());
+    assert!(game_state.route_to_destination().is_none());
+
+    for (pickup, destination) in [("far", "near"), ("near", "far")] {
+        game_state.passengers.push(Passenger {
+            pickup: pickup.to_owned(),
+            destination: destination.to_owned(),
+            state: PassengerState::Waiting,
+            spawned_at: 0.0,
+            damage: 0.0,
+        });
+    }
+    assert_near(&game_state.destination().unwrap(), (150.0, 50.0));
+
+    game_state.passengers[1].state = PassengerState::Riding { picked_up_at: 0.0 };
+    assert_near(&game_state.destination().unwrap(), (950.0, 50.0));
+    let route = game_state.route_to_destination().unwrap();
+    assert_near(route.points.last().unwrap(), (950.0, 50.0));
+}
@@ -0,0 +1,279 @@
+//! Tricks, points and the combo multiplier.
+
+mod common;
+
+use taxi_sim::constants::{
+    COMBO_HOLD_TIME, COMBO_STEP, NEAR_MISS_MIN_SPEED, NEAR_MISS_POINTS, PERFECT_DASH_POINTS,
+    SIMULATION_TICK_RATE,
+};
+use taxi_sim::events::GameEvent;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::Level;
+use taxi_sim::math::{Point, polygon_distance};
+use taxi_sim::passenger::{Passenger, PassengerState};
+use taxi_sim::player::PlayerAction;
+use taxi_sim::scoring::Trick;
+use taxi_sim::stats::Stats;
+use taxi_sim::wall::Wall;
+
+/// Nothing in the way, and no test wall.
+fn empty_game() -> GameState {
+    GameState::from_level(&Level::parse("Level(spawn: (position: (x: 0.0, y: 0.0)))").unwrap())
+}
+
+/// Returns every `Scored` event from the ticks it ran, as (trick, points,
+/// combo).
+fn run(
+    game_state: &mut GameState,
+    actions: &[PlayerAction],
+    ticks: usize,
+) -> Vec<(Trick, u32, f32)> {
+    common::run_events(game_state, actions, ticks)
+        .into_iter()
+        .filter_map(|event| match event {
+            GameEvent::Scored {
+                trick,
+                points,
+                combo,
+                ..
+            } => Some((trick, points, combo)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Neutral, drive, then the gas `ticks` after drive (6 is perfect, 10 is
+/// only good). Returns what was scored on the way.
+fn dash(game_state: &mut GameState, ticks: usize) -> Vec<(Trick, u32, f32)> {
+    let mut scored = run(game_state, &[PlayerAction::ShiftIntoNeutral], 1);
+    scored.extend(run(game_state, &[PlayerAction::ShiftIntoDrive], ticks));
+    scored.extend(run(
+        game_state,
+        &[PlayerAction::GasActivated, PlayerAction::GasHeld],
+        1,
+    ));
+    scored
+}
+
+fn square(x: f32, y: f32, size: f32) -> Vec<Point> {
+    vec![
+        Point::new(x, y),
+        Point::new(x + size, y),
+        Point::new(x + size, y + size),
+        Point::new(x, y + size),
+    ]
+}
+
+/// Tall wall starting `gap` to the right of the taxi, from a little way ahead
+/// of it to a long way ahead.
+fn wall_alongside(game_state: &mut GameState, gap: f32) {
+    let bounds = game_state.player.bounds();
+    let left = bounds.x + bounds.w + gap;
+    game_state.add_wall(Wall::from_points(
+        vec![
+            Point::new(left, -800.0),
+            Point::new(left + 100.0, -800.0),
+            Point::new(left + 100.0, -300.0),
+            Point::new(left, -300.0),
+        ],
+        0.0,
+    ));
+}
+
+#[test]
+fn polygon_distance_is_between_the_outlines() {
+    let a = square(0.0, 0.0, 10.0);
+    assert_eq!(polygon_distance(&a, &square(15.0, 0.0, 10.0)), 5.0);
+    // Corner to corner.
+    assert_eq!(polygon_distance(&a, &square(13.0, 14.0, 10.0)), 5.0);
+    // Touching and overlapping.
+    assert_eq!(polygon_distance(&a, &square(10.0, 0.0, 10.0)), 0.0);
+    assert_eq!(polygon_distance(&a, &square(5.0, 5.0, 10.0)), 0.0);
+}
+
+#[test]
+fn perfect_dashes_score_and_good_ones_dont() {
+    let mut game_state = empty_game();
+    let scored = dash(&mut game_state, 6);
+    assert_eq!(scored.len(), 1);
+    let (trick, points, combo) = scored[0];
+    assert_eq!(trick, Trick::PerfectDash);
+    assert!(points >= PERFECT_DASH_POINTS as u32);
+    assert_eq!(combo, 1.0);
+    assert_eq!(game_state.scoring.total, points);
+
+    let mut game_state = empty_game();
+    assert!(dash(&mut game_state, 10).is_empty());
+    assert_eq!(game_state.scoring.total, 0);
+}
+
+#[test]
+fn tricks_in_a_row_build_the_combo_then_it_wears_off() {
+    let mut game_state = empty_game();
+    dash(&mut game_state, 6);
+    run(&mut game_state, &[], 80);
+    let scored = dash(&mut game_state, 6);
+
+    assert_eq!(scored.len(), 1);
+    assert_eq!(scored[0].2, 1.0 + COMBO_STEP);
+    assert_eq!(game_state.scoring.combo, 1.0 + COMBO_STEP * 2.0);
+
+    // Held for a bit...
+    run(
+        &mut game_state,
+        &[],
+        (SIMULATION_TICK_RATE as f64 * COMBO_HOLD_TIME) as usize - 10,
+    );
+    assert_eq!(game_state.scoring.combo, 1.0 + COMBO_STEP * 2.0);
+    // ...then back down to nothing.
+    run(&mut game_state, &[], SIMULATION_TICK_RATE as usize * 3);
+    assert_eq!(game_state.scoring.combo, 1.0);
+}
+
+#[test]
+fn passing_close_to_a_wall_is_a_near_miss() {
+    let mut game_state = empty_game();
+    wall_alongside(&mut game_state, 15.0);
+    let scored = run(&mut game_state, &[PlayerAction::GasHeld], 240);
+
+    assert_eq!(scored.len(), 1);
+    let (trick, points, _) = scored[0];
+    assert_eq!(trick, Trick::NearMiss);
+    assert!(points > NEAR_MISS_POINTS as u32 && points < NEAR_MISS_POINTS as u32 * 2);
+}
+
+#[test]
+fn far_or_slow_passes_arent_near_misses() {
+    let mut game_state = empty_game();
+    wall_alongside(&mut game_state, 100.0);
+    assert!(run(&mut game_state, &[PlayerAction::GasHeld], 240).is_empty());
+
+    // Let go of the gas early, so it's crawling by the time it gets there.
+    let mut game_state = empty_game();
+    wall_alongside(&mut game_state, 15.0);
+    run(&mut game_state, &[PlayerAction::GasHeld], 30);
+    assert!(run(&mut game_state, &[], 480).is_empty());
+}
+
+#[test]
+fn hitting_the_wall_isnt_a_near_miss() {
+    let mut game_state = empty_game();
+    game_state.add_wall(Wall::from_points(square(-200.0, -700.0, 400.0), 0.0));
+    let scored = run(&mut game_state, &[PlayerAction::GasHeld], 240);
+
+    assert!(scored.iter().all(|(trick, _, _)| *trick != Trick::NearMiss));
+}
+
+#[test]
+fn scraping_the_wall_then_pulling_away_isnt_a_near_miss() {
+    for overlap in [0.5, 2.0, 5.0] {
+        let mut game_state = empty_game();
+        // Sticking a little way into the taxi's path, so it clips the corner
+        // and gets pushed out alongside the wall, still going fast.
+        wall_alongside(&mut game_state, -overlap);
+        let events = common::run_events(&mut game_state, &[PlayerAction::GasHeld], 240);
+
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, GameEvent::Collision { .. })),
+            "never hit the wall with {} overlap",
+            overlap
+        );
+        // Still fast enough that it's not just too slow to count.
+        assert!(game_state.player.speed() >= NEAR_MISS_MIN_SPEED);
+        assert!(
+            events.iter().all(|event| !matches!(
+                event,
+                GameEvent::Scored {
+                    trick: Trick::NearMiss,
+                    ..
+                }
+            )),
+            "scraping with {} overlap was a near miss",
+            overlap
+        );
+    }
+}
+
+#[test]
+fn long_drifts_score_when_they_end() {
+    let mut game_state = empty_game();
+    dash(&mut game_state, 6);
+    let during = run(
+        &mut game_state,
+        &[PlayerAction::GasHeld, PlayerAction::Steer(1.0)],
+        90,
+    );
+    assert!(game_state.player.drifting);
+    assert!(during.iter().all(|(trick, _, _)| *trick != Trick::Drift));
+
+    let after = run(&mut game_state, &[], 240);
+    assert!(!game_state.player.drifting);
+    assert!(after.iter().any(|(trick, _, _)| *trick == Trick::Drift));
+}
+
+#[test]
+fn crashing_drops_the_combo() {
+    let mut game_state = empty_game();
+    dash(&mut game_state, 6);
+    assert!(game_state.scoring.combo > 1.0);
+
+    // Dashing straight into a wall, well before the combo wears off.
+    game_state.add_wall(Wall::from_points(square(-200.0, -500.0, 400.0), 0.0));
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    assert_eq!(game_state.scoring.combo, 1.0);
+}
+
+#[test]
+fn pickups_straight_out_of_high_speed_score() {
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            regions: [
+                (name: \"a\", area: (x: -100.0, y: -1200.0, w: 200.0, h: 300.0), kind: Pickup),
+                (name: \"b\", area: (x: 900.0, y: -100.0, w: 200.0, h: 200.0), kind: Pickup),
+            ],
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+    game_state.passengers.push(Passenger {
+        pickup: "a".to_owned(),
+        destination: "b".to_owned(),
+        state: PassengerState::Waiting,
+        spawned_at: 0.0,
+        damage: 0.0,
+    });
+
+    // Flat out, then slam on the brakes in the zone.
+    let mut scored = run(&mut game_state, &[PlayerAction::GasHeld], 240);
+    scored.extend(run(&mut game_state, &[PlayerAction::Brake], 120));
+
+    assert!(
+        game_state.riding_passenger().is_some(),
+        "taxi ended up at {:?}",
+        game_state.player.center
+    );
+    assert!(
+        scored
+            .iter()
+            .any(|(trick, _, _)| *trick == Trick::HighSpeedPickup)
+    );
+}
+
+#[test]
+fn stats_count_tricks_and_the_best_combo() {
+    let mut stats = Stats::new();
+    for combo in [1.0, 2.5, 1.5] {
+        stats.record(&GameEvent::Scored {
+            trick: Trick::Drift,
+            points: 100,
+            combo,
+            position: Point::new(0.0, 0.0),
+        });
+    }
+
+    assert_eq!(stats.tricks, 3);
+    assert_eq!(stats.best_combo, 2.5);
+}
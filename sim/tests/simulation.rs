@@ -0,0 +1,435 @@
+//! Drives `simulate` with scripted input, no window needed.
+
+mod common;
+
+use common::{DELTA_TIME, run, total_impact_speed};
+use taxi_sim::constants::{PLAYER_MAX_VELOCITY, SHIFT_LENGTH};
+use taxi_sim::gamestate::{GameState, simulate};
+use taxi_sim::input::InputFrame;
+use taxi_sim::player::{Player, PlayerAction, ShiftMode};
+use taxi_sim::wall::Wall;
+
+/// Note `GameState::new` also puts a test wall 300px below the player.
+fn new_game() -> GameState {
+    GameState::new(Player::new(0.0, 0.0))
+}
+
+fn frame(actions: &[PlayerAction]) -> InputFrame {
+    let mut input_frame = InputFrame::new();
+    input_frame.player_actions.extend_from_slice(actions);
+    input_frame
+}
+
+#[test]
+fn idle_taxi_stays_put() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[], 120);
+
+    assert_eq!(game_state.player.center.x, 0.0);
+    assert_eq!(game_state.player.center.y, 0.0);
+    assert_eq!(game_state.player.speed(), 0.0);
+}
+
+#[test]
+fn clock_advances_one_tick_per_simulate() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[], 120);
+
+    assert!((game_state.time - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn shift_ends_on_the_sim_clock() {
+    let mut game_state = new_game();
+    assert_eq!(game_state.shift_time_left(), SHIFT_LENGTH);
+
+    // Just before the end...
+    game_state.time = SHIFT_LENGTH - DELTA_TIME as f64 * 1.5;
+    run(&mut game_state, &[], 1);
+    assert!(!game_state.is_shift_over());
+    assert!(game_state.shift_time_left() > 0.0);
+
+    // ...and just after.
+    run(&mut game_state, &[], 1);
+    assert!(game_state.is_shift_over());
+    assert_eq!(game_state.shift_time_left(), 0.0);
+}
+
+#[test]
+fn gas_in_drive_moves_forward() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+
+    // Facing up by default, so forward is -y.
+    assert!(game_state.player.center.y < -10.0);
+    assert_eq!(game_state.player.center.x, 0.0);
+    assert!(game_state.player.velocity.y < 0.0);
+    assert!(game_state.player.forward_speed() > 0.0);
+}
+
+#[test]
+fn speed_is_capped_without_dashing() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 600);
+
+    assert!(game_state.player.speed() <= PLAYER_MAX_VELOCITY + 10.0);
+}
+
+#[test]
+fn taxi_coasts_to_a_stop() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    run(&mut game_state, &[], 600);
+
+    assert_eq!(game_state.player.speed(), 0.0);
+}
+
+#[test]
+fn gas_in_reverse_moves_backward() {
+    let mut game_state = new_game();
+    run(
+        &mut game_state,
+        &[PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld],
+        30,
+    );
+
+    assert_eq!(game_state.player.shift_mode, ShiftMode::REVERSE);
+    assert!(game_state.player.center.y > 0.0);
+}
+
+/// Ticks until the taxi stops, holding `actions`.
+fn ticks_to_stop(game_state: &mut GameState, actions: &[PlayerAction]) -> usize {
+    let mut ticks = 0;
+    while game_state.player.speed() != 0.0 {
+        run(game_state, actions, 1);
+        ticks += 1;
+        assert!(ticks < 10_000, "taxi never stopped");
+    }
+    ticks
+}
+
+#[test]
+fn brake_stops_sooner_than_coasting() {
+    let mut coasting = new_game();
+    run(&mut coasting, &[PlayerAction::GasHeld], 60);
+    let mut braking = new_game();
+    run(&mut braking, &[PlayerAction::GasHeld], 60);
+
+    let coasting_ticks = ticks_to_stop(&mut coasting, &[]);
+    let braking_ticks = ticks_to_stop(&mut braking, &[PlayerAction::Brake]);
+
+    assert!(braking_ticks < coasting_ticks);
+    // Braking doesn't push the taxi backwards once it's stopped.
+    run(&mut braking, &[PlayerAction::Brake], 60);
+    assert_eq!(braking.player.speed(), 0.0);
+}
+
+#[test]
+fn reverse_waits_until_the_taxi_slows_down() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+
+    run(&mut game_state, &[PlayerAction::ShiftIntoReverse], 1);
+    assert_eq!(game_state.player.shift_mode, ShiftMode::NEUTRAL);
+
+    // Holding reverse while braking engages it once slow enough.
+    run(
+        &mut game_state,
+        &[PlayerAction::ShiftIntoReverse, PlayerAction::Brake],
+        120,
+    );
+    assert_eq!(game_state.player.shift_mode, ShiftMode::REVERSE);
+}
+
+#[test]
+fn gas_in_neutral_does_nothing() {
+    let mut game_state = new_game();
+    run(
+        &mut game_state,
+        &[PlayerAction::ShiftIntoNeutral, PlayerAction::GasHeld],
+        60,
+    );
+
+    assert_eq!(game_state.player.shift_mode, ShiftMode::NEUTRAL);
+    assert_eq!(game_state.player.speed(), 0.0);
+    assert_eq!(game_state.player.center.y, 0.0);
+}
+
+#[test]
+fn park_only_engages_when_stopped() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+
+    run(&mut game_state, &[PlayerAction::ShiftIntoPark], 1);
+    assert_eq!(game_state.player.shift_mode, ShiftMode::DRIVE);
+
+    run(&mut game_state, &[PlayerAction::Brake], 120);
+    run(&mut game_state, &[PlayerAction::ShiftIntoPark], 1);
+    assert_eq!(game_state.player.shift_mode, ShiftMode::PARK);
+
+    // Gas does nothing while parked.
+    let parked_at = game_state.player.center;
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    assert_eq!(game_state.player.center.y, parked_at.y);
+}
+
+#[test]
+fn turning_only_works_while_moving() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::Steer(1.0)], 60);
+    assert_eq!(game_state.player.forward_normal.x, 0.0);
+
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasHeld, PlayerAction::Steer(1.0)],
+        60,
+    );
+    assert!(game_state.player.forward_normal.x > 0.0);
+    assert!(game_state.player.center.x > 0.0);
+}
+
+#[test]
+fn half_steer_turns_less_than_full_lock() {
+    let heading_after = |steer: f32| {
+        let mut game_state = new_game();
+        run(&mut game_state, &[PlayerAction::GasHeld], 60);
+        run(
+            &mut game_state,
+            &[PlayerAction::GasHeld, PlayerAction::Steer(steer)],
+            30,
+        );
+        game_state.player.forward_normal.x
+    };
+
+    let half = heading_after(0.5);
+    let full = heading_after(1.0);
+    assert!(half > 0.0);
+    assert!(half < full);
+    // Past full lock is still full lock.
+    assert_eq!(heading_after(3.0), full);
+    assert!(heading_after(-0.5) < 0.0);
+}
+
+fn reposition(x: f32, y: f32, reset_motion: bool) -> PlayerAction {
+    PlayerAction::Reposition { x, y, reset_motion }
+}
+
+/// Driving up and to the right at speed.
+fn moving_and_turned() -> GameState {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasHeld, PlayerAction::Steer(1.0)],
+        20,
+    );
+    game_state
+}
+
+#[test]
+fn reposition_keeps_heading_and_velocity() {
+    let mut game_state = moving_and_turned();
+    let forward_normal = game_state.player.forward_normal;
+    let velocity = game_state.player.velocity;
+
+    // Same tick as the teleport, so the taxi hasn't moved on from it yet.
+    simulate(
+        &frame(&[reposition(500.0, 250.0, false)]),
+        &mut game_state,
+        0.0,
+    );
+
+    let player = &game_state.player;
+    assert_eq!(player.center.x, 500.0);
+    assert_eq!(player.center.y, 250.0);
+    assert_eq!(player.forward_normal.x, forward_normal.x);
+    assert_eq!(player.forward_normal.y, forward_normal.y);
+    // Not exact, the velocity gets split up and put back together each tick.
+    assert!((player.velocity.x - velocity.x).abs() < 0.001);
+    assert!((player.velocity.y - velocity.y).abs() < 0.001);
+    // Still the same shape around the new center.
+    let average_x = player.points.iter().map(|point| point.x).sum::<f32>() / 8.0;
+    assert!((average_x - 500.0).abs() < 0.01);
+}
+
+#[test]
+fn reposition_can_reset_heading_and_velocity() {
+    let mut game_state = moving_and_turned();
+    run(&mut game_state, &[reposition(500.0, 250.0, true)], 1);
+
+    let player = &game_state.player;
+    assert_eq!(player.center.x, 500.0);
+    assert_eq!(player.center.y, 250.0);
+    assert_eq!(player.forward_normal.x, 0.0);
+    assert_eq!(player.forward_normal.y, -1.0);
+    assert_eq!(player.speed(), 0.0);
+}
+
+#[test]
+fn reposition_doesnt_interpolate_across_the_teleport() {
+    let mut game_state = moving_and_turned();
+    simulate(
+        &frame(&[reposition(500.0, 250.0, false)]),
+        &mut game_state,
+        0.0,
+    );
+
+    let halfway = game_state.player.interpolated_center(0.5);
+    assert_eq!(halfway.x, 500.0);
+    assert_eq!(halfway.y, 250.0);
+}
+
+#[test]
+fn walls_stop_the_taxi() {
+    let mut game_state = new_game();
+    let wall_top = game_state
+        .walls()
+        .iter()
+        .map(|wall| wall.bounds().y)
+        .fold(f32::MAX, f32::min);
+
+    // Reverse straight down into the test wall.
+    run(
+        &mut game_state,
+        &[PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld],
+        600,
+    );
+
+    let player_bottom = game_state.player.bounds();
+    assert!(player_bottom.y + player_bottom.h <= wall_top + 1.0);
+}
+
+#[test]
+fn hitting_a_wall_reports_the_impact() {
+    let mut game_state = new_game();
+    let mut hardest_impact: f32 = 0.0;
+    let mut input_frame = frame(&[PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld]);
+    for _ in 0..600 {
+        simulate(&input_frame, &mut game_state, DELTA_TIME);
+        input_frame.remove_one_shot_actions();
+        hardest_impact = hardest_impact.max(total_impact_speed(&game_state.events));
+    }
+
+    assert!(hardest_impact > 100.0);
+    // Just resting against it afterwards doesn't count.
+    assert!(total_impact_speed(&game_state.events) < hardest_impact);
+}
+
+#[test]
+fn walls_can_be_added_and_removed() {
+    let mut game_state = new_game();
+    let index = game_state.add_wall(Wall::new(0.0, -300.0));
+    run(&mut game_state, &[PlayerAction::GasHeld], 120);
+    assert!(game_state.player.center.y > -300.0);
+
+    game_state.remove_wall(index);
+    run(&mut game_state, &[PlayerAction::GasHeld], 240);
+    assert!(game_state.player.center.y < -300.0);
+}
+
+#[test]
+fn crazy_dash_needs_gas_right_after_shifting_into_drive() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::ShiftIntoReverse], 10);
+    run(&mut game_state, &[PlayerAction::ShiftIntoDrive], 1);
+    // ~0.05 seconds later
+    run(&mut game_state, &[PlayerAction::ShiftIntoDrive], 5);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasActivated, PlayerAction::GasHeld],
+        1,
+    );
+
+    assert!(game_state.player.is_crazy_dashing);
+
+    run(&mut game_state, &[PlayerAction::GasHeld], 60);
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn gas_too_late_after_drive_isnt_a_crazy_dash() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::ShiftIntoReverse], 10);
+    run(&mut game_state, &[PlayerAction::ShiftIntoDrive], 60);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasActivated, PlayerAction::GasHeld],
+        1,
+    );
+
+    assert!(!game_state.player.is_crazy_dashing);
+}
+
+#[test]
+fn tires_grip_when_turning_at_normal_speed() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::GasHeld], 120);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasHeld, PlayerAction::Steer(1.0)],
+        60,
+    );
+
+    assert!(!game_state.player.drifting);
+    assert!(game_state.player.lateral_speed().abs() < 0.01);
+    assert!(game_state.player.drift_angle().abs() < 0.001);
+}
+
+#[test]
+fn turning_hard_mid_dash_drifts() {
+    let mut game_state = new_game();
+    run(&mut game_state, &[PlayerAction::ShiftIntoReverse], 10);
+    run(&mut game_state, &[PlayerAction::ShiftIntoDrive], 6);
+    run(
+        &mut game_state,
+        &[PlayerAction::GasActivated, PlayerAction::GasHeld],
+        1,
+    );
+    run(
+        &mut game_state,
+        &[PlayerAction::GasHeld, PlayerAction::Steer(1.0)],
+        30,
+    );
+
+    // Car turned right, but it's still going more straight than it's facing,
+    // so it's sliding out to its left.
+    assert!(game_state.player.drifting);
+    assert!(game_state.player.lateral_speed() < 0.0);
+    assert!(game_state.player.drift_angle() < 0.0);
+
+    // Tires catch it again once it lets off and straightens out.
+    run(&mut game_state, &[], 120);
+    assert!(!game_state.player.drifting);
+}
+
+#[test]
+fn same_inputs_give_the_same_result() {
+    let script = [
+        (vec![PlayerAction::GasHeld], 90),
+        (vec![PlayerAction::GasHeld, PlayerAction::Steer(-1.0)], 45),
+        (
+            vec![PlayerAction::ShiftIntoReverse, PlayerAction::GasHeld],
+            30,
+        ),
+        (
+            vec![PlayerAction::ShiftIntoDrive, PlayerAction::GasActivated],
+            1,
+        ),
+        (vec![PlayerAction::GasHeld, PlayerAction::Steer(1.0)], 120),
+    ];
+
+    let mut runs = Vec::new();
+    for _ in 0..2 {
+        let mut game_state = new_game();
+        for (actions, ticks) in script.iter() {
+            run(&mut game_state, actions, *ticks);
+        }
+        runs.push(game_state);
+    }
+
+    assert_eq!(runs[0].player.center.x, runs[1].player.center.x);
+    assert_eq!(runs[0].player.center.y, runs[1].player.center.y);
+    assert_eq!(runs[0].player.velocity.x, runs[1].player.velocity.x);
+    assert_eq!(runs[0].player.velocity.y, runs[1].player.velocity.y);
+}
@@ -0,0 +1,264 @@
+//! Tiles, chunks, and buildings turning into walls as the taxi gets near.
+
+mod common;
+
+use common::{run, run_events};
+use taxi_sim::constants::{CHUNK_TILES, SIMULATION_TICK_RATE, TILE_SIZE};
+use taxi_sim::events::{GameEvent, Obstacle};
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::{Level, LevelError};
+use taxi_sim::math::{Point, Rect};
+use taxi_sim::player::PlayerAction;
+use taxi_sim::tilemap::{ChunkCoord, Tile, Tilemap};
+
+fn rows(rows: &[&str]) -> Vec<String> {
+    rows.iter().map(|row| row.to_string()).collect()
+}
+
+/// A chunk's width in world units.
+fn chunk_size() -> f32 {
+    CHUNK_TILES as f32 * TILE_SIZE
+}
+
+fn teleport(x: f32, y: f32) -> PlayerAction {
+    PlayerAction::Reposition {
+        x,
+        y,
+        reset_motion: true,
+    }
+}
+
+#[test]
+fn rows_are_placed_from_the_origin() {
+    let tilemap = Tilemap::from_rows((-1, 2), &rows(&["._", "# "])).unwrap();
+
+    assert_eq!(tilemap.get(-1, 2), Tile::Road);
+    assert_eq!(tilemap.get(0, 2), Tile::Sidewalk);
+    assert_eq!(tilemap.get(-1, 3), Tile::Building);
+    assert_eq!(tilemap.get(0, 3), Tile::Empty);
+    // Off the edge of what was written.
+    assert_eq!(tilemap.get(5, 5), Tile::Empty);
+    // Tile -1 is in the chunk left of the origin.
+    assert_eq!(tilemap.chunk_count(), 2);
+}
+
+#[test]
+fn unknown_tiles_are_an_error() {
+    let text = "Level(
+        spawn: (position: (x: 0.0, y: 0.0)),
+        tiles: (rows: [\"..\", \".?\"]),
+    )";
+    assert!(matches!(
+        Level::parse(text),
+        Err(LevelError::InvalidTile { row: 1, column: 1 })
+    ));
+}
+
+#[test]
+fn buildings_merge_into_rectangles() {
+    let tilemap = Tilemap::from_rows((0, 0), &rows(&["###.", "###.", "....", ".#.."])).unwrap();
+    let outlines = tilemap
+        .chunk(ChunkCoord::new(0, 0))
+        .unwrap()
+        .building_outlines(ChunkCoord::new(0, 0));
+
+    assert_eq!(
+        outlines,
+        vec![
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(3.0 * TILE_SIZE, 0.0),
+                Point::new(3.0 * TILE_SIZE, 2.0 * TILE_SIZE),
+                Point::new(0.0, 2.0 * TILE_SIZE),
+            ],
+            vec![
+                Point::new(TILE_SIZE, 3.0 * TILE_SIZE),
+                Point::new(2.0 * TILE_SIZE, 3.0 * TILE_SIZE),
+                Point::new(2.0 * TILE_SIZE, 4.0 * TILE_SIZE),
+                Point::new(TILE_SIZE, 4.0 * TILE_SIZE),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn buildings_split_at_chunk_edges() {
+    // Two tiles either side of the line between chunk 0 and chunk 1.
+    let mut tilemap = Tilemap::new();
+    for x in CHUNK_TILES as i32 - 2..CHUNK_TILES as i32 + 2 {
+        tilemap.set(x, 0, Tile::Building);
+    }
+
+    let left = tilemap.chunk(ChunkCoord::new(0, 0)).unwrap();
+    let right = tilemap.chunk(ChunkCoord::new(1, 0)).unwrap();
+    assert_eq!(left.building_outlines(ChunkCoord::new(0, 0)).len(), 1);
+    assert_eq!(right.building_outlines(ChunkCoord::new(1, 0)).len(), 1);
+}
+
+#[test]
+fn chunks_stream_in_and_out_around_an_area() {
+    let mut game_state =
+        GameState::from_level(&Level::parse("Level(spawn: (position: (x: 0.0, y: 0.0)))").unwrap());
+    // One building in each of two chunks, far apart.
+    let far = CHUNK_TILES as i32 * 10;
+    game_state.tilemap.set(0, 0, Tile::Building);
+    game_state.tilemap.set(far, 0, Tile::Building);
+
+    game_state.stream_tilemap(&[Rect::new(0.0, 0.0, 10.0, 10.0)]);
+    assert_eq!(game_state.walls().len(), 1);
+    assert_eq!(
+        game_state.loaded_chunks().copied().collect::<Vec<_>>(),
+        vec![ChunkCoord::new(0, 0)]
+    );
+
+    let far_x = far as f32 * TILE_SIZE;
+    game_state.stream_tilemap(&[Rect::new(far_x, 0.0, 10.0, 10.0)]);
+    assert_eq!(game_state.walls().len(), 1);
+    assert_eq!(game_state.walls()[0].center, Point::new(far_x + 50.0, 50.0));
+
+    // Both at once (with one area or two), then neither.
+    game_state.stream_tilemap(&[Rect::new(0.0, 0.0, far_x + 10.0, 10.0)]);
+    assert_eq!(game_state.walls().len(), 2);
+    game_state.stream_tilemap(&[
+        Rect::new(0.0, 0.0, 10.0, 10.0),
+        Rect::new(far_x, 0.0, 10.0, 10.0),
+    ]);
+    assert_eq!(game_state.walls().len(), 2);
+    game_state.stream_tilemap(&[Rect::new(-5000.0, -5000.0, 10.0, 10.0)]);
+    assert!(game_state.walls().is_empty());
+}
+
+#[test]
+fn unloading_keeps_other_walls_working() {
+    let mut game_state =
+        GameState::from_level(&Level::parse("Level(spawn: (position: (x: 0.0, y: 0.0)))").unwrap());
+    // Lots of separate buildings in three chunks in a row, so removing the
+    // middle one moves walls from the last one around.
+    for chunk in 0..3 {
+        for building in 0..4 {
+            let x = chunk * CHUNK_TILES as i32 + building * 2;
+            game_state.tilemap.set(x, 0, Tile::Building);
+        }
+    }
+    let all = Rect::new(0.0, 0.0, chunk_size() * 3.0 - 1.0, 10.0);
+    game_state.stream_tilemap(&[all]);
+    assert_eq!(game_state.walls().len(), 12);
+
+    // The first two chunks' walls go, and the last chunk's get moved down
+    // into their places.
+    let last_chunk = Rect::new(chunk_size() * 2.0, 0.0, chunk_size() - 1.0, 10.0);
+    game_state.stream_tilemap(&[last_chunk]);
+    assert_eq!(game_state.walls().len(), 4);
+    assert_eq!(game_state.walls_in(&last_chunk).count(), 4);
+    for wall in game_state.walls() {
+        assert!(wall.center.x > chunk_size() * 2.0);
+    }
+
+    // And they all come back, once each.
+    game_state.stream_tilemap(&[all]);
+    assert_eq!(game_state.walls().len(), 12);
+}
+
+#[test]
+fn walls_follow_the_taxi() {
+    let far = CHUNK_TILES as i32 * 10;
+    let text = format!(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            tiles: (origin: ({}, 0), rows: [\"#\"]),
+        )",
+        far
+    );
+    let mut game_state = GameState::from_level(&Level::parse(&text).unwrap());
+    assert!(game_state.walls().is_empty());
+
+    let far_x = far as f32 * TILE_SIZE;
+    run(&mut game_state, &[teleport(far_x - 500.0, 0.0)], 1);
+    assert_eq!(game_state.walls().len(), 1);
+
+    run(&mut game_state, &[teleport(0.0, 0.0)], 1);
+    assert!(game_state.walls().is_empty());
+}
+
+#[test]
+fn traffic_far_from_the_taxi_hits_buildings_too() {
+    // A lane running into a building, well out of the taxi's streaming
+    // distance.
+    let far = CHUNK_TILES as i32 * 4;
+    let far_x = far as f32 * TILE_SIZE;
+    let text = format!(
+        "Level(
+            spawn: (position: (x: 0.0, y: 0.0)),
+            tiles: (origin: ({}, -2), rows: [\"#\", \"#\", \"#\", \"#\", \"#\"]),
+            lanes: [(points: [(x: {}, y: 50.0), (x: {}, y: 50.0)], cars: 1)],
+        )",
+        far,
+        far_x - 1500.0,
+        far_x + 1500.0
+    );
+    let mut game_state = GameState::from_level(&Level::parse(&text).unwrap());
+    let building_chunk = ChunkCoord::of_tile(far, 0);
+    assert!(
+        !game_state
+            .loaded_chunks()
+            .any(|coord| *coord == building_chunk)
+    );
+
+    let mut closest_x = f32::MIN;
+    for _ in 0..(10 * SIMULATION_TICK_RATE as usize) {
+        run(&mut game_state, &[], 1);
+        closest_x = closest_x.max(game_state.traffic[0].car.center.x);
+    }
+
+    // Got there and was stopped by it, rather than driving through.
+    assert!(closest_x > far_x - 100.0, "only got to {}", closest_x);
+    assert!(closest_x < far_x, "drove through to {}", closest_x);
+    assert!(
+        game_state
+            .loaded_chunks()
+            .any(|coord| *coord == building_chunk)
+    );
+}
+
+#[test]
+fn taxi_hits_building_tiles() {
+    // A row of buildings across the road ahead, 3 tiles up.
+    let level = Level::parse(
+        "Level(
+            spawn: (position: (x: 50.0, y: 50.0)),
+            tiles: (origin: (-3, -3), rows: [\"#######\"]),
+        )",
+    )
+    .unwrap();
+    let mut game_state = GameState::from_level(&level);
+
+    let events = run_events(
+        &mut game_state,
+        &[PlayerAction::GasHeld],
+        SIMULATION_TICK_RATE as usize * 2,
+    );
+    let hit = events.iter().any(|event| {
+        matches!(
+            event,
+            GameEvent::Collision {
+                with: Obstacle::Wall,
+                ..
+            }
+        )
+    });
+
+    assert!(hit);
+    // Stopped below the buildings, not driven through them.
+    assert!(game_state.player.center.y > -200.0);
+}
+
+#[test]
+fn bundled_tile_level_loads() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../levels/tiles.ron");
+    let level = Level::load(path).unwrap();
+    let game_state = GameState::from_level(&level);
+
+    let spawn = Tilemap::tile_at(&game_state.player.center);
+    assert_eq!(game_state.tilemap.get(spawn.0, spawn.1), Tile::Road);
+    assert!(!game_state.walls().is_empty());
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use common::{run, total_impact_speed};
+use taxi_sim::constants::{
+    SIMULATION_TICK_RATE, TOW_DELAY, TRAFFIC_CRUISE_SPEED, TRAFFIC_STOP_DISTANCE,
+};
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::Level;
+use taxi_sim::math::{Point, polygon_collision};
+use taxi_sim::player::PlayerAction;
+use taxi_sim::traffic::TrafficCar;
+use taxi_sim::tuning::Tuning;
+
+/// Taxi at the origin facing up, nothing else but the given lanes.
+fn game_with_lanes(lanes: &str) -> GameState {
+    let text = format!(
+        "Level(spawn: (position: (x: 0.0, y: 0.0)), lanes: [{}])",
+        lanes
+    );
+    GameState::from_level(&Level::parse(&text).unwrap())
+}
+
+/// Big octagon around the origin, clockwise.
+const LOOP_LANE: &str = "(points: [
+    (x: -600.0, y: -900.0), (x: 600.0, y: -900.0), (x: 1000.0, y: -500.0), (x: 1000.0, y: 500.0),
+    (x: 600.0, y: 1000.0), (x: -600.0, y: 1000.0), (x: -1000.0, y: 500.0), (x: -1000.0, y: -500.0),
+], cars: 1)";
+
+#[test]
+fn cars_spawn_spread_out_facing_along_the_lane() {
+    let game_state = game_with_lanes(
+        "(points: [(x: 0.0, y: -500.0), (x: 500.0, y: -500.0), (x: 500.0, y: 500.0), (x: 0.0, y: 500.0)], cars: 2)",
+    );
+
+    assert_eq!(game_state.traffic.len(), 2);
+    let first = &game_state.traffic[0];
+    assert_eq!(first.car.center.x, 0.0);
+    assert_eq!(first.car.center.y, -500.0);
+    assert_eq!(first.next_waypoint, 1);
+    // Facing right, towards the next point.
+    assert!((first.car.forward_normal.x - 1.0).abs() < 1e-5);
+
+    let second = &game_state.traffic[1];
+    assert_eq!(second.car.center.x, 500.0);
+    assert_eq!(second.car.center.y, 500.0);
+    assert_eq!(second.next_waypoint, 3);
+}
+
+#[test]
+fn cars_drive_laps_around_their_lane() {
+    let mut game_state = game_with_lanes(LOOP_LANE);
+
+    let mut waypoints_reached = 0;
+    let mut last_waypoint = game_state.traffic[0].next_waypoint;
+    let mut top_speed: f32 = 0.0;
+    for _ in 0..(60 * SIMULATION_TICK_RATE as usize) {
+        run(&mut game_state, &[], 1);
+
+        let traffic_car = &game_state.traffic[0];
+        if traffic_car.next_waypoint != last_waypoint {
+            waypoints_reached += 1;
+            last_waypoint = traffic_car.next_waypoint;
+        }
+        top_speed = top_speed.max(traffic_car.car.speed());
+
+        // Never wanders far off the octagon.
+        let center = traffic_car.car.center;
+        assert!(center.x.abs() < 1200.0 && center.y > -1100.0 && center.y < 1200.0);
+        assert!(center.x.abs() > 200.0 || center.y.abs() > 200.0);
+    }
+
+    assert!(waypoints_reached > 8, "only reached {}", waypoints_reached);
+    // Gas overshoots by a tick's worth at most.
+    assert!(top_speed <= TRAFFIC_CRUISE_SPEED + 10.0);
+}
+
+/// Top speed of the only car over `ticks`, starting from a standstill.
+fn traffic_top_speed(game_state: &mut GameState, ticks: usize) -> f32 {
+    let mut top_speed: f32 = 0.0;
+    for _ in 0..ticks {
+        run(game_state, &[], 1);
+        top_speed = top_speed.max(game_state.traffic[0].car.speed());
+    }
+    top_speed
+}
+
+#[test]
+fn traffic_follows_the_tuning() {
+    let tuning = Tuning::parse("gas_velocity = 1500.0\nplayer_max_velocity = 200.0\n").unwrap();
+    let mut game_state = game_with_lanes(LOOP_LANE);
+    game_state.set_tuning(tuning.clone());
+    assert_eq!(game_state.traffic[0].car.tuning, tuning);
+
+    // Accelerates quicker than the default tuning would...
+    let mut default_game_state = game_with_lanes(LOOP_LANE);
+    let quarter_second = SIMULATION_TICK_RATE as usize / 4;
+    assert!(
+        traffic_top_speed(&mut game_state, quarter_second)
+            > traffic_top_speed(&mut default_game_state, quarter_second) + 50.0
+    );
+    // ...but tops out well under cruising speed.
+    let top_speed = traffic_top_speed(&mut game_state, 5 * SIMULATION_TICK_RATE as usize);
+    // Again, gas overshoots by a tick's worth at most.
+    let overshoot = 1500.0 / SIMULATION_TICK_RATE;
+    assert!(top_speed <= 200.0 + overshoot, "got up to {}", top_speed);
+    assert!(top_speed > 190.0, "only got up to {}", top_speed);
+}
+
+#[test]
+fn cars_stop_for_the_taxi_in_front_of_them() {
+    // Lane runs straight up through the taxi.
+    let mut game_state =
+        game_with_lanes("(points: [(x: 0.0, y: 800.0), (x: 0.0, y: -2000.0)], cars: 1)");
+    run(&mut game_state, &[], 8 * SIMULATION_TICK_RATE as usize);
+
+    let car = &game_state.traffic[0].car;
+    let gap = car.center.y - game_state.player.center.y;
+    assert!(car.speed() < 1.0);
+    assert!(gap > TRAFFIC_STOP_DISTANCE * 0.8, "stopped {} away", gap);
+    assert!(gap < TRAFFIC_STOP_DISTANCE * 1.5, "stopped {} away", gap);
+    // Never touched it.
+    assert_eq!(game_state.player.center.y, 0.0);
+}
+
+#[test]
+fn wrecks_get_towed_out_of_the_lane() {
+    let mut game_state = game_with_lanes(LOOP_LANE);
+    // A second car on the last stretch before the first one, so it catches
+    // up to it straight away.
+    let follower = TrafficCar::new(&game_state.lanes, 0, 7);
+    game_state.traffic.push(follower);
+    game_state.traffic[0].car.health = 0.0;
+    let wreck_center = game_state.traffic[0].car.center;
+
+    // Stuck behind the wreck until it's towed.
+    let tow_ticks = (TOW_DELAY * SIMULATION_TICK_RATE as f64) as usize;
+    run(&mut game_state, &[], tow_ticks - 10);
+    let (wreck, follower) = (&game_state.traffic[0], &game_state.traffic[1]);
+    assert!(wreck.car.is_wrecked());
+    assert!((wreck.car.center - wreck_center).length() < 1.0);
+    // Still creeping up to where it stops, but not getting past.
+    assert!(follower.car.speed() < 20.0);
+    assert!((follower.car.center - wreck_center).length() < TRAFFIC_STOP_DISTANCE * 1.5);
+    assert_eq!(follower.next_waypoint, 0);
+
+    // Then it's back on the road somewhere else, and the lane's moving.
+    run(&mut game_state, &[], 20);
+    let towed = &game_state.traffic[0].car;
+    assert!(!towed.is_wrecked());
+    assert!((towed.center - wreck_center).length() > 500.0);
+    run(&mut game_state, &[], 5 * SIMULATION_TICK_RATE as usize);
+    assert_ne!(game_state.traffic[1].next_waypoint, 0);
+    assert!(game_state.traffic[1].car.speed() > TRAFFIC_CRUISE_SPEED / 2.0);
+    assert!(game_state.traffic[0].car.speed() > TRAFFIC_CRUISE_SPEED / 2.0);
+}
+
+#[test]
+fn taxi_rams_traffic() {
+    // Traffic drives up, away from the taxi, slower than the taxi can go.
+    let mut game_state =
+        game_with_lanes("(points: [(x: 0.0, y: -300.0), (x: 0.0, y: -5000.0)], cars: 1)");
+
+    let mut impact_speed: f32 = 0.0;
+    for _ in 0..(3 * SIMULATION_TICK_RATE as usize) {
+        run(&mut game_state, &[PlayerAction::GasHeld], 1);
+        impact_speed = impact_speed.max(total_impact_speed(&game_state.events));
+
+        let overlap =
+            polygon_collision(&game_state.player.points, &game_state.traffic[0].car.points);
+        assert!(overlap.is_none_or(|collision| collision.overlap < 1.0));
+    }
+
+    assert!(impact_speed > 0.0);
+    // Got shoved along faster than it drives by itself.
+    assert!(game_state.traffic[0].car.speed() > TRAFFIC_CRUISE_SPEED);
+}
+
+#[test]
+fn traffic_cars_bump_each_other_apart() {
+    let mut game_state = game_with_lanes(
+        "(points: [(x: -1000.0, y: 0.0), (x: 1000.0, y: 0.0)], cars: 1),
+         (points: [(x: 1000.0, y: 100.0), (x: -1000.0, y: 100.0)], cars: 1)",
+    );
+    // Move them away from the taxi and nose to nose into each other.
+    game_state.traffic[0].car.reposition(-35.0, 1000.0, false);
+    game_state.traffic[1].car.reposition(35.0, 1000.0, false);
+    game_state.traffic[0].car.velocity = Point::new(100.0, 0.0);
+    game_state.traffic[1].car.velocity = Point::new(-100.0, 0.0);
+
+    run(&mut game_state, &[], 1);
+
+    let (a, b) = (&game_state.traffic[0].car, &game_state.traffic[1].car);
+    assert!(polygon_collision(&a.points, &b.points).is_none_or(|c| c.overlap < 1.0));
+    // Bounced off each other.
+    assert!(a.velocity.x < 0.0);
+    assert!(b.velocity.x > 0.0);
+    assert!(game_state.events.is_empty());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::temp_path;
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+use taxi_sim::player::Player;
+use taxi_sim::tuning::{Tuning, TuningError, TuningWatcher};
+
+#[test]
+fn missing_values_use_the_defaults() {
+    let tuning = Tuning::parse("gas_velocity = 1234.0\n").unwrap();
+
+    assert_eq!(tuning.gas_velocity, 1234.0);
+    assert_eq!(
+        Tuning {
+            gas_velocity: Tuning::default().gas_velocity,
+            ..tuning
+        },
+        Tuning::default()
+    );
+}
+
+#[test]
+fn to_toml_parses_back_the_same() {
+    let tuning = Tuning {
+        crazy_dash_input_timing: 0.01..0.2,
+        ..Tuning::default()
+    };
+
+    assert_eq!(Tuning::parse(&tuning.to_toml()).unwrap(), tuning);
+}
+
+#[test]
+fn wrong_types_are_a_parse_error() {
+    assert!(matches!(
+        Tuning::parse("gas_velocity = \"fast\"\n"),
+        Err(TuningError::Parse(_))
+    ));
+}
+
+#[test]
+fn non_positive_values_are_rejected_by_name() {
+    for (text, bad_field) in [
+        ("player_max_velocity = 0.0\n", "player_max_velocity"),
+        ("gas_velocity = -960.0\n", "gas_velocity"),
+        ("crazy_dash_cooldown = -1.0\n", "crazy_dash_cooldown"),
+        ("car_default_drag = -0.5\n", "car_default_drag"),
+        (
+            "player_max_reverse_velocity = 350.0\n",
+            "player_max_reverse_velocity",
+        ),
+        (
+            "crazy_dash_input_timing = { start = 0.2, end = 0.1 }\n",
+            "crazy_dash_input_timing",
+        ),
+        // Infinity's valid TOML, but not a speed.
+        ("gas_velocity = inf\n", "gas_velocity"),
+        ("crazy_dash_drag = inf\n", "crazy_dash_drag"),
+        (
+            "player_max_reverse_velocity = -inf\n",
+            "player_max_reverse_velocity",
+        ),
+        (
+            "crazy_dash_perfect_timing = { start = 0.0, end = inf }\n",
+            "crazy_dash_perfect_timing",
+        ),
+        (
+            "[player_shape]\nhalf_width = 0.0\nhalf_height = 40.0\n\
+             inner_space_w = 10.0\ninner_space_h = 15.0\n",
+            "player_shape.half_width",
+        ),
+    ] {
+        match Tuning::parse(text) {
+            Err(error @ TuningError::Invalid { field, .. }) => {
+                assert_eq!(field, bad_field);
+                assert!(error.to_string().contains(bad_field));
+            }
+            other => panic!("{:?} gave {:?}", text, other),
+        }
+    }
+
+    // Zero's fine where it just turns something off.
+    assert!(Tuning::parse("car_default_drag = 0.0\n").is_ok());
+}
+
+#[test]
+fn changing_the_shape_rebuilds_the_car() {
+    let mut player = Player::new(0.0, 0.0);
+    let mut tuning = Tuning::default();
+    tuning.player_shape.half_width *= 2.0;
+    player.set_tuning(tuning);
+
+    assert!((player.bounds().w - Tuning::default().player_shape.half_width * 4.0).abs() < 0.01);
+}
+
+#[test]
+fn watcher_reloads_when_the_file_changes() {
+    let path = temp_path("watch", "toml");
+    let mut watcher = TuningWatcher::new(&path);
+    assert!(watcher.poll().is_none(), "file doesn't exist yet");
+
+    std::fs::write(&path, "gas_velocity = 100.0\n").unwrap();
+    assert_eq!(watcher.poll().unwrap().unwrap().gas_velocity, 100.0);
+    assert!(watcher.poll().is_none(), "nothing changed");
+
+    // Modified times can be pretty coarse, so bump it by hand rather than
+    // relying on the write being a tick later.
+    std::fs::write(&path, "gas_velocity = 200.0\n").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(10);
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert_eq!(watcher.poll().unwrap().unwrap().gas_velocity, 200.0);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn checked_in_tuning_matches_the_defaults() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../tuning.toml");
+
+    assert_eq!(Tuning::load(path).unwrap(), Tuning::default());
+}
@@ -0,0 +1,171 @@
+//! Textures for the textured view, listed in a TOML manifest, e.g.
+//!
+//! ```toml
+//! [textures]
+//! car = "car.png"
+//! wall = "wall.png"
+//! ground = "ground.png"
+//! ```
+//!
+//! Paths are relative to the manifest. Anything missing or broken is swapped
+//! for a checkerboard (with a warning), so a bad asset never stops the game
+//! from starting.
+
+use macroquad::miniquad::TextureWrap;
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_PATH: &str = "assets/manifest.toml";
+
+/// Every texture the game asks the manifest for.
+const TEXTURE_NAMES: [&str; 3] = ["car", "wall", "ground"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AssetManifest {
+    /// Name to path, relative to the manifest.
+    #[serde(default)]
+    pub textures: BTreeMap<String, PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetError::Io(error) => write!(f, "couldn't read asset manifest: {}", error),
+            AssetError::Parse(error) => write!(f, "couldn't parse asset manifest: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<io::Error> for AssetError {
+    fn from(error: io::Error) -> Self {
+        AssetError::Io(error)
+    }
+}
+
+impl AssetManifest {
+    /// Texture paths come back relative to wherever `path` is.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let mut manifest = Self::parse(&text)?;
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        for texture_path in manifest.textures.values_mut() {
+            *texture_path = directory.join(&texture_path);
+        }
+        Ok(manifest)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, AssetError> {
+        toml::from_str(text).map_err(AssetError::Parse)
+    }
+}
+
+pub struct Assets {
+    /// Facing up, white so it can be tinted for the taxi and traffic.
+    pub car: Texture2D,
+    /// Repeats, for filling wall polygons.
+    pub wall: Texture2D,
+    /// Repeats under everything.
+    pub ground: Texture2D,
+}
+
+impl Assets {
+    /// Loads everything in the manifest at `path`, drawing a loading screen
+    /// in between so the window isn't just black.
+    pub async fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let manifest = AssetManifest::load(path).unwrap_or_else(|error| {
+            eprintln!("{}: {}", path.display(), error);
+            AssetManifest::default()
+        });
+
+        let mut textures = BTreeMap::new();
+        for (index, name) in TEXTURE_NAMES.iter().enumerate() {
+            draw_loading_screen(index as f32 / TEXTURE_NAMES.len() as f32, name);
+            next_frame().await;
+
+            let texture = match manifest.textures.get(*name) {
+                Some(texture_path) => match load_texture(&texture_path.to_string_lossy()).await {
+                    Ok(texture) => texture,
+                    Err(error) => {
+                        eprintln!("{}: {}", texture_path.display(), error);
+                        checkerboard()
+                    }
+                },
+                None => {
+                    eprintln!("{}: no \"{}\" texture", path.display(), name);
+                    checkerboard()
+                }
+            };
+            textures.insert(*name, texture);
+        }
+        draw_loading_screen(1.0, "");
+        next_frame().await;
+
+        let assets = Self {
+            car: textures["car"].clone(),
+            wall: textures["wall"].clone(),
+            ground: textures["ground"].clone(),
+        };
+        set_repeat(&assets.wall);
+        set_repeat(&assets.ground);
+        assets
+    }
+}
+
+/// `progress` from 0.0 to 1.0, `name` is what's loading right now.
+fn draw_loading_screen(progress: f32, name: &str) {
+    let (width, height) = (400.0, 20.0);
+    let x = (screen_width() - width) / 2.0;
+    let y = screen_height() / 2.0;
+
+    clear_background(BLACK);
+    draw_text("Loading...", x, y - 20.0, 30.0, WHITE);
+    draw_rectangle(x, y, width * progress, height, YELLOW);
+    draw_rectangle_lines(x, y, width, height, 2.0, WHITE);
+    draw_text(name, x, y + height + 25.0, 20.0, GRAY);
+}
+
+/// Magenta and black, so it's obvious what's missing.
+fn checkerboard() -> Texture2D {
+    let size = 16;
+    let mut pixels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let on = (x / 8 + y / 8) % 2 == 0;
+            pixels.extend_from_slice(if on {
+                &[255, 0, 255, 255]
+            } else {
+                &[0, 0, 0, 255]
+            });
+        }
+    }
+    Texture2D::from_rgba8(size as u16, size as u16, &pixels)
+}
+
+/// Makes UVs outside 0..1 wrap around instead of stretching the edge pixels,
+/// which macroquad doesn't have a function for.
+fn set_repeat(texture: &Texture2D) {
+    // Safe as long as nothing else is using the GL context right now, which
+    // it isn't between frames on the main thread.
+    let gl = unsafe { get_internal_gl() };
+    gl.quad_context.texture_set_wrap(
+        texture.raw_miniquad_id(),
+        TextureWrap::Repeat,
+        TextureWrap::Repeat,
+    );
+}
@@ -0,0 +1,214 @@
+//! Engine, tires, gear changes, dashes and crashes, behind the `audio`
+//! feature (macroquad's audio needs libasound on Linux). Without it `Audio`
+//! just doesn't make a sound, so the rest of the game doesn't need to care
+//! whether it was built in.
+//!
+//! Muting skips loading and playing anything at all, so `--mute` works on
+//! machines without an audio device too.
+
+use taxi_sim::events::GameEvent;
+use taxi_sim::player::Player;
+
+/// Master volume when there's no `--volume`.
+pub const DEFAULT_VOLUME: f32 = 0.8;
+
+#[cfg(feature = "audio")]
+mod sounds {
+    use crate::synth;
+    use macroquad::audio::{
+        PlaySoundParams, Sound, load_sound_from_bytes, play_sound, set_sound_volume,
+    };
+    use taxi_sim::events::GameEvent;
+    use taxi_sim::player::{Player, ShiftMode};
+
+    /// Engine volume when idling...
+    const ENGINE_IDLE_VOLUME: f32 = 0.25;
+    /// ...and at crazy dash max velocity.
+    const ENGINE_FULL_VOLUME: f32 = 0.7;
+    /// Sliding sideways this fast is the loudest squeal.
+    const SQUEAL_FULL_LATERAL_SPEED: f32 = 400.0;
+    /// Collisions softer than this don't make a sound...
+    const CRASH_MIN_IMPACT_SPEED: f32 = 60.0;
+    /// ...and this hard or harder is full volume.
+    const CRASH_LOUDEST_IMPACT_SPEED: f32 = 700.0;
+
+    pub struct Sounds {
+        /// One for each of `synth::ENGINE_PITCHES`.
+        engine: Vec<Sound>,
+        squeal: Sound,
+        drive: Sound,
+        reverse: Sound,
+        /// Neutral and park.
+        shift: Sound,
+        whoosh: Sound,
+        crash: Sound,
+
+        volume: f32,
+        loops_started: bool,
+        /// Loudest crash since the last `update`, so a pile up of collisions
+        /// in one frame is one bang rather than a dozen.
+        pending_crash: f32,
+    }
+
+    impl Sounds {
+        pub async fn load(volume: f32) -> Self {
+            let mut engine = Vec::new();
+            for pitch in synth::ENGINE_PITCHES {
+                engine.push(load(synth::engine(pitch)).await);
+            }
+
+            Self {
+                engine,
+                squeal: load(synth::squeal()).await,
+                drive: load(synth::blip(300.0, 700.0)).await,
+                reverse: load(synth::blip(500.0, 180.0)).await,
+                shift: load(synth::blip(220.0, 160.0)).await,
+                whoosh: load(synth::whoosh()).await,
+                crash: load(synth::crash()).await,
+                volume,
+                loops_started: false,
+                pending_crash: 0.0,
+            }
+        }
+
+        pub fn handle_event(&mut self, event: &GameEvent) {
+            match event {
+                GameEvent::GearChanged { to, .. } => {
+                    let sound = match to {
+                        ShiftMode::DRIVE => &self.drive,
+                        ShiftMode::REVERSE => &self.reverse,
+                        ShiftMode::NEUTRAL | ShiftMode::PARK => &self.shift,
+                    };
+                    self.play(sound, 0.6);
+                }
+                GameEvent::DashStarted { strength, .. } => {
+                    self.play(&self.whoosh, (0.7 * strength).min(1.0));
+                }
+                GameEvent::Collision { impact_speed, .. }
+                    if *impact_speed > CRASH_MIN_IMPACT_SPEED =>
+                {
+                    let loudness = (impact_speed / CRASH_LOUDEST_IMPACT_SPEED).min(1.0);
+                    self.pending_crash = self.pending_crash.max(loudness);
+                }
+                GameEvent::Wrecked => self.pending_crash = 1.0,
+                _ => (),
+            }
+        }
+
+        pub fn update(&mut self, taxi: &Player) {
+            if !self.loops_started {
+                for sound in self.engine.iter().chain([&self.squeal]) {
+                    play_sound(
+                        sound,
+                        PlaySoundParams {
+                            looped: true,
+                            volume: 0.0,
+                        },
+                    );
+                }
+                self.loops_started = true;
+            }
+
+            // Fade between the engine loops either side of how fast we're
+            // going, as if the pitch was going up.
+            let speed_fraction =
+                (taxi.speed() / taxi.tuning.crazy_dash_max_velocity).clamp(0.0, 1.0);
+            let engine_volume = if taxi.is_wrecked() {
+                0.0
+            } else {
+                ENGINE_IDLE_VOLUME + (ENGINE_FULL_VOLUME - ENGINE_IDLE_VOLUME) * speed_fraction
+            };
+            let position = speed_fraction * (self.engine.len() - 1) as f32;
+            for (index, sound) in self.engine.iter().enumerate() {
+                let weight = (1.0 - (position - index as f32).abs()).max(0.0);
+                set_sound_volume(sound, engine_volume * weight * self.volume);
+            }
+
+            let squeal = if taxi.drifting {
+                (taxi.lateral_speed().abs() / SQUEAL_FULL_LATERAL_SPEED).min(1.0)
+            } else {
+                0.0
+            };
+            set_sound_volume(&self.squeal, squeal * 0.5 * self.volume);
+
+            if self.pending_crash > 0.0 {
+                self.play(&self.crash, self.pending_crash);
+                self.pending_crash = 0.0;
+            }
+        }
+
+        /// Turns the engine and tires down, until the next `update`.
+        pub fn silence(&mut self) {
+            for sound in self.engine.iter().chain([&self.squeal]) {
+                set_sound_volume(sound, 0.0);
+            }
+            self.pending_crash = 0.0;
+        }
+
+        fn play(&self, sound: &Sound, volume: f32) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume: volume * self.volume,
+                },
+            );
+        }
+    }
+
+    async fn load(wav: Vec<u8>) -> Sound {
+        // Only fails to load files, which these aren't.
+        load_sound_from_bytes(&wav)
+            .await
+            .expect("generated sounds are valid WAVs")
+    }
+}
+
+pub struct Audio {
+    #[cfg(feature = "audio")]
+    sounds: Option<sounds::Sounds>,
+}
+
+impl Audio {
+    /// `volume` is the master volume, 0.0 to 1.0.
+    #[cfg(feature = "audio")]
+    pub async fn new(volume: f32, muted: bool) -> Self {
+        let volume = volume.clamp(0.0, 1.0);
+        let sounds = if muted || volume == 0.0 {
+            None
+        } else {
+            Some(sounds::Sounds::load(volume).await)
+        };
+        Self { sounds }
+    }
+
+    #[cfg(not(feature = "audio"))]
+    pub async fn new(_volume: f32, _muted: bool) -> Self {
+        Self {}
+    }
+
+    /// Call for each of the simulation's events.
+    pub fn handle_event(&mut self, _event: &GameEvent) {
+        #[cfg(feature = "audio")]
+        if let Some(sounds) = self.sounds.as_mut() {
+            sounds.handle_event(_event);
+        }
+    }
+
+    /// Call once a frame, after simulating.
+    pub fn update(&mut self, _taxi: &Player) {
+        #[cfg(feature = "audio")]
+        if let Some(sounds) = self.sounds.as_mut() {
+            sounds.update(_taxi);
+        }
+    }
+
+    /// Call instead of `update` on frames nothing's being simulated (in
+    /// menus, paused), so the engine doesn't keep running.
+    pub fn silence(&mut self) {
+        #[cfg(feature = "audio")]
+        if let Some(sounds) = self.sounds.as_mut() {
+            sounds.silence();
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Which keys and gamepad buttons do what, loaded from a TOML file, e.g.
+//!
+//! ```toml
+//! steer_axis = "LeftStickX"
+//! steer_dead_zone = 0.15
+//!
+//! [keys]
+//! gas = ["Space", "W"]
+//! steer_left = ["Left", "A"]
+//!
+//! [gamepad_buttons]
+//! gas = ["RightTrigger2"]
+//! ```
+//!
+//! Anything left out keeps its default binding; bind something to `[]` to
+//! unbind it.
+
+use crate::input::{DebugAction, MenuAction};
+use macroquad::input::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Things the player can do with the taxi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Control {
+    Gas,
+    Brake,
+    Drive,
+    Reverse,
+    Neutral,
+    Park,
+    SteerLeft,
+    SteerRight,
+}
+
+/// A keyboard key, written by name in the bindings file ("Space", "A",
+/// "Key1", ...). Only the keys in `KEY_NAMES` can be bound.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key(pub KeyCode);
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        const KEY_NAMES: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+    };
+}
+
+key_names!(
+    Space,
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+    LeftShift,
+    RightShift,
+    LeftControl,
+    RightControl,
+    LeftAlt,
+    RightAlt,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Minus,
+    Equal,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+);
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        KEY_NAMES
+            .iter()
+            .find(|(_, key_name)| *key_name == name)
+            .map(|(key, _)| Key(*key))
+            .ok_or_else(|| format!("unknown key \"{}\"", name))
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        KEY_NAMES
+            .iter()
+            .find(|(key_code, _)| *key_code == key.0)
+            .map(|(_, name)| name.to_string())
+            // Can't happen for keys that were loaded from a file.
+            .unwrap_or_else(|| format!("{:?}", key.0))
+    }
+}
+
+/// Gamepad buttons, named the same as gilrs names them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    /// Stick used for analog steering, left is negative.
+    pub steer_axis: Option<PadAxis>,
+    /// Stick movement smaller than this is ignored (sticks rarely rest at
+    /// exactly 0.0).
+    pub steer_dead_zone: f32,
+    pub keys: BTreeMap<Control, Vec<Key>>,
+    pub gamepad_buttons: BTreeMap<Control, Vec<PadButton>>,
+    pub debug_keys: BTreeMap<DebugAction, Vec<Key>>,
+    pub menu_keys: BTreeMap<MenuAction, Vec<Key>>,
+    pub menu_buttons: BTreeMap<MenuAction, Vec<PadButton>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let keys = [
+            (Control::Gas, vec![Key(KeyCode::Space)]),
+            (Control::Brake, vec![Key(KeyCode::LeftShift)]),
+            (Control::Drive, vec![Key(KeyCode::Up)]),
+            (Control::Reverse, vec![Key(KeyCode::Down)]),
+            (Control::Neutral, vec![Key(KeyCode::N)]),
+            (Control::Park, vec![Key(KeyCode::P)]),
+            (Control::SteerLeft, vec![Key(KeyCode::Left)]),
+            (Control::SteerRight, vec![Key(KeyCode::Right)]),
+        ];
+        let gamepad_buttons = [
+            (
+                Control::Gas,
+                vec![PadButton::RightTrigger2, PadButton::South],
+            ),
+            (
+                Control::Brake,
+                vec![PadButton::LeftTrigger2, PadButton::West],
+            ),
+            (Control::Drive, vec![PadButton::DPadUp]),
+            (Control::Reverse, vec![PadButton::DPadDown]),
+            (Control::Neutral, vec![PadButton::North]),
+            (Control::Park, vec![PadButton::Select]),
+            (Control::SteerLeft, vec![PadButton::DPadLeft]),
+            (Control::SteerRight, vec![PadButton::DPadRight]),
+        ];
+        let debug_keys = [
+            (DebugAction::ToggleText, vec![Key(KeyCode::Key1)]),
+            (DebugAction::ToggleConstants, vec![Key(KeyCode::Key2)]),
+            (
+                DebugAction::ToggleCrazyDashIndicator,
+                vec![Key(KeyCode::Key3)],
+            ),
+            (DebugAction::ToggleGrid, vec![Key(KeyCode::Key4)]),
+            (DebugAction::ToggleTuningPanel, vec![Key(KeyCode::Key5)]),
+            (DebugAction::ToggleWireframe, vec![Key(KeyCode::Key6)]),
+        ];
+        let menu_keys = [
+            (MenuAction::Up, vec![Key(KeyCode::Up), Key(KeyCode::W)]),
+            (MenuAction::Down, vec![Key(KeyCode::Down), Key(KeyCode::S)]),
+            (
+                MenuAction::Confirm,
+                vec![Key(KeyCode::Enter), Key(KeyCode::Space)],
+            ),
+            (MenuAction::Pause, vec![Key(KeyCode::Escape)]),
+        ];
+        let menu_buttons = [
+            (MenuAction::Up, vec![PadButton::DPadUp]),
+            (MenuAction::Down, vec![PadButton::DPadDown]),
+            (MenuAction::Confirm, vec![PadButton::South]),
+            (MenuAction::Pause, vec![PadButton::Start]),
+        ];
+
+        Self {
+            steer_axis: Some(PadAxis::LeftStickX),
+            steer_dead_zone: 0.15,
+            keys: keys.into(),
+            gamepad_buttons: gamepad_buttons.into(),
+            debug_keys: debug_keys.into(),
+            menu_keys: menu_keys.into(),
+            menu_buttons: menu_buttons.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    /// `steer_dead_zone` has to be at least 0.0 and less than 1.0.
+    InvalidDeadZone(f32),
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BindingsError::Io(error) => write!(f, "couldn't read bindings: {}", error),
+            BindingsError::Parse(error) => write!(f, "couldn't parse bindings: {}", error),
+            BindingsError::InvalidDeadZone(dead_zone) => write!(
+                f,
+                "steer_dead_zone is {}, but has to be from 0.0 up to (not including) 1.0",
+                dead_zone
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
+
+impl From<io::Error> for BindingsError {
+    fn from(error: io::Error) -> Self {
+        BindingsError::Io(error)
+    }
+}
+
+impl Bindings {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BindingsError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, BindingsError> {
+        let mut bindings: Bindings = toml::from_str(text).map_err(BindingsError::Parse)?;
+        // 1.0 or more would ignore the whole stick (and divide by zero).
+        if !(0.0..1.0).contains(&bindings.steer_dead_zone) {
+            return Err(BindingsError::InvalidDeadZone(bindings.steer_dead_zone));
+        }
+
+        // Controls the file doesn't mention keep their defaults, so adding a
+        // new control doesn't leave it unbound for everyone with an old file.
+        let defaults = Bindings::default();
+        for (control, keys) in defaults.keys {
+            bindings.keys.entry(control).or_insert(keys);
+        }
+        for (control, buttons) in defaults.gamepad_buttons {
+            bindings.gamepad_buttons.entry(control).or_insert(buttons);
+        }
+        for (debug_action, keys) in defaults.debug_keys {
+            bindings.debug_keys.entry(debug_action).or_insert(keys);
+        }
+        for (menu_action, keys) in defaults.menu_keys {
+            bindings.menu_keys.entry(menu_action).or_insert(keys);
+        }
+        for (menu_action, buttons) in defaults.menu_buttons {
+            bindings.menu_buttons.entry(menu_action).or_insert(buttons);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        // Only fails for types TOML can't represent, and there aren't any here.
+        let text = toml::to_string(self).expect("bindings should always serialize");
+        std::fs::write(path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_bindings_keep_their_defaults() {
+        let bindings = Bindings::parse(
+            "steer_dead_zone = 0.3\n\
+             [keys]\n\
+             gas = [\"W\"]\n\
+             brake = []\n",
+        )
+        .unwrap();
+        let defaults = Bindings::default();
+
+        assert_eq!(bindings.steer_dead_zone, 0.3);
+        assert_eq!(bindings.keys[&Control::Gas], vec![Key(KeyCode::W)]);
+        assert!(
+            bindings.keys[&Control::Brake].is_empty(),
+            "unbound on purpose"
+        );
+        assert_eq!(
+            bindings.keys[&Control::Drive],
+            defaults.keys[&Control::Drive]
+        );
+        assert_eq!(bindings.gamepad_buttons, defaults.gamepad_buttons);
+        assert_eq!(bindings.debug_keys, defaults.debug_keys);
+        assert_eq!(bindings.menu_keys, defaults.menu_keys);
+        assert_eq!(bindings.menu_buttons, defaults.menu_buttons);
+    }
+
+    #[test]
+    fn empty_file_is_the_defaults() {
+        assert_eq!(Bindings::parse("").unwrap(), Bindings::default());
+    }
+
+    #[test]
+    fn unknown_keys_are_a_parse_error() {
+        assert!(matches!(
+            Bindings::parse("[keys]\ngas = [\"Hyper\"]\n"),
+            Err(BindingsError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn dead_zones_outside_0_to_1_are_rejected() {
+        for dead_zone in ["-0.1", "1.0", "2.5", "nan"] {
+            let text = format!("steer_dead_zone = {}\n", dead_zone);
+            assert!(
+                matches!(
+                    Bindings::parse(&text),
+                    Err(BindingsError::InvalidDeadZone(_))
+                ),
+                "{}",
+                dead_zone
+            );
+        }
+        assert!(Bindings::parse("steer_dead_zone = 0.0\n").is_ok());
+        assert!(Bindings::parse("steer_dead_zone = 0.99\n").is_ok());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::constants::*;
+use macroquad::rand::gen_range;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use taxi_sim::events::GameEvent;
+use taxi_sim::math::{
+    Point, Rect, convert_camera_pos_to_world_pos, convert_world_pos_to_camera_pos,
+};
+use taxi_sim::tuning::TuningError;
+
+/// The `[camera]` table of the tuning file. It's only for rendering, so it
+/// lives here rather than in the sim's `Tuning` (which gets saved into
+/// replays). See `constants.rs` for what each of these does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraTuning {
+    pub speed: f32,
+    pub look_ahead: f32,
+    pub max_look_ahead: f32,
+    pub dead_zone: f32,
+    pub min_zoom: f32,
+    pub zoom_rate: f32,
+    pub shake_max_offset: f32,
+    pub shake_decay: f32,
+    pub shake_impact_speed: f32,
+    pub dash_shake: f32,
+}
+
+impl Default for CameraTuning {
+    fn default() -> Self {
+        Self {
+            speed: CAMERA_SPEED,
+            look_ahead: CAMERA_LOOK_AHEAD,
+            max_look_ahead: CAMERA_MAX_LOOK_AHEAD,
+            dead_zone: CAMERA_DEAD_ZONE,
+            min_zoom: CAMERA_MIN_ZOOM,
+            zoom_rate: CAMERA_ZOOM_RATE,
+            shake_max_offset: CAMERA_SHAKE_MAX_OFFSET,
+            shake_decay: CAMERA_SHAKE_DECAY,
+            shake_impact_speed: CAMERA_SHAKE_IMPACT_SPEED,
+            dash_shake: CAMERA_DASH_SHAKE,
+        }
+    }
+}
+
+/// Everything else in the tuning file is the sim's business.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TuningFile {
+    camera: CameraTuning,
+}
+
+impl CameraTuning {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TuningError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    /// Picks the `[camera]` table out of a whole tuning file.
+    pub fn parse(text: &str) -> Result<Self, TuningError> {
+        let file: TuningFile = toml::from_str(text).map_err(TuningError::Parse)?;
+        Ok(file.camera)
+    }
+
+    /// Just the `[camera]` table, to go after `Tuning::to_toml`.
+    pub fn to_toml(&self) -> String {
+        let file = TuningFile {
+            camera: self.clone(),
+        };
+        toml::to_string(&file).expect("camera tuning should always serialize")
+    }
+}
+
+/// Follows the taxi around and turns world positions into screen positions.
+/// Everything drawn in the world should go through `world_to_screen`.
+pub struct Camera {
+    /// World position in the middle of the screen (before shake).
+    pub center: Point,
+    /// Screen pixels per world unit. Less than 1.0 is zoomed out.
+    pub zoom: f32,
+    /// Size of the screen in pixels.
+    pub screen_size: Point,
+    /// The view is never allowed outside of this.
+    pub bounds: Rect,
+
+    /// 0.0 to 1.0, how hard the screen is shaking. Wears off over time.
+    trauma: f32,
+    /// This frame's shake, in world units.
+    shake_offset: Point,
+}
+
+impl Camera {
+    pub fn new(center: Point, screen_size: Point, bounds: Rect) -> Self {
+        Self {
+            center,
+            zoom: 1.0,
+            screen_size,
+            bounds,
+            trauma: 0.0,
+            shake_offset: Point::new(0.0, 0.0),
+        }
+    }
+
+    /// Ran every rendered frame (not every simulation tick) with the
+    /// interpolated position, so it follows smoothly.
+    /// Zooms all the way out at `top_speed`.
+    pub fn update(
+        &mut self,
+        target: &Point,
+        velocity: &Point,
+        top_speed: f32,
+        delta_time: f32,
+        tuning: &CameraTuning,
+    ) {
+        self.update_zoom(velocity.length() / top_speed, delta_time, tuning);
+
+        // Look where the taxi is going, not where it is.
+        let mut look_ahead = *velocity * tuning.look_ahead;
+        if look_ahead.length() > tuning.max_look_ahead {
+            look_ahead = look_ahead.normalized() * tuning.max_look_ahead;
+        }
+        let offset = *target + look_ahead - self.center;
+
+        // Small movements inside the dead zone don't move the camera at all,
+        // so it isn't constantly wobbling around.
+        let follow = Point::new(
+            outside_dead_zone(offset.x, tuning.dead_zone),
+            outside_dead_zone(offset.y, tuning.dead_zone),
+        );
+        self.center = self.center + follow * (tuning.speed * delta_time).min(1.0);
+
+        self.keep_in_view(target);
+        self.clamp_to_bounds();
+        self.update_shake(delta_time, tuning);
+    }
+
+    /// `amount` of 1.0 is the hardest shake; shakes add up.
+    pub fn shake(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    /// Shakes for crashes (by how hard the taxi hit) and dashes.
+    pub fn handle_event(&mut self, event: &GameEvent, tuning: &CameraTuning) {
+        match event {
+            GameEvent::Collision { impact_speed, .. } => {
+                self.shake(impact_speed / tuning.shake_impact_speed);
+            }
+            GameEvent::DashStarted { strength, .. } => {
+                self.shake(tuning.dash_shake * strength);
+            }
+            _ => (),
+        }
+    }
+
+    pub fn world_to_screen(&self, world_pos: &Point) -> Point {
+        convert_world_pos_to_camera_pos(world_pos, &self.view_rect()) * self.zoom
+    }
+
+    /// Inverse of `world_to_screen`, e.g. for mouse clicks.
+    pub fn screen_to_world(&self, screen_pos: &Point) -> Point {
+        convert_camera_pos_to_world_pos(&(*screen_pos * (1.0 / self.zoom)), &self.view_rect())
+    }
+
+    /// Turns a world distance into screen pixels.
+    pub fn scale(&self, world_length: f32) -> f32 {
+        world_length * self.zoom
+    }
+
+    /// What's on screen, in world space. Use this for culling.
+    pub fn view_rect(&self) -> Rect {
+        let half_size = self.half_view_size();
+        let view_center = self.center + self.shake_offset;
+        Rect::new(
+            view_center.x - half_size.x,
+            view_center.y - half_size.y,
+            half_size.x * 2.0,
+            half_size.y * 2.0,
+        )
+    }
+
+    fn half_view_size(&self) -> Point {
+        self.screen_size * (0.5 / self.zoom)
+    }
+
+    fn update_zoom(&mut self, speed_fraction: f32, delta_time: f32, tuning: &CameraTuning) {
+        // Zoom out the faster we go.
+        let speed_fraction = speed_fraction.clamp(0.0, 1.0);
+        let target_zoom = 1.0 - (1.0 - tuning.min_zoom) * speed_fraction;
+
+        self.zoom += (target_zoom - self.zoom) * (tuning.zoom_rate * delta_time).min(1.0);
+    }
+
+    /// However laggy the camera is tuned, don't ever let the taxi get close
+    /// to the edge of the screen.
+    fn keep_in_view(&mut self, target: &Point) {
+        let max_offset = self.half_view_size() * 0.8;
+        let offset = *target - self.center;
+
+        self.center.x += outside_dead_zone(offset.x, max_offset.x);
+        self.center.y += outside_dead_zone(offset.y, max_offset.y);
+    }
+
+    fn clamp_to_bounds(&mut self) {
+        let half_size = self.half_view_size();
+        let bounds_center = self.bounds.center();
+
+        // If the view is bigger than the bounds, just show the middle.
+        self.center.x = if half_size.x * 2.0 >= self.bounds.w {
+            bounds_center.x
+        } else {
+            self.center.x.clamp(
+                self.bounds.x + half_size.x,
+                self.bounds.x + self.bounds.w - half_size.x,
+            )
+        };
+        self.center.y = if half_size.y * 2.0 >= self.bounds.h {
+            bounds_center.y
+        } else {
+            self.center.y.clamp(
+                self.bounds.y + half_size.y,
+                self.bounds.y + self.bounds.h - half_size.y,
+            )
+        };
+    }
+
+    fn update_shake(&mut self, delta_time: f32, tuning: &CameraTuning) {
+        self.trauma = (self.trauma - tuning.shake_decay * delta_time).max(0.0);
+
+        // Squared so small shakes are subtle and big ones are big.
+        let strength = self.trauma * self.trauma * tuning.shake_max_offset / self.zoom;
+        self.shake_offset = Point::new(
+            gen_range(-1.0, 1.0) * strength,
+            gen_range(-1.0, 1.0) * strength,
+        );
+    }
+}
+
+/// How far `offset` goes past `dead_zone` either way, or 0.0 if it's inside.
+fn outside_dead_zone(offset: f32, dead_zone: f32) -> f32 {
+    if offset > dead_zone {
+        offset - dead_zone
+    } else if offset < -dead_zone {
+        offset + dead_zone
+    } else {
+        0.0
+    }
+}
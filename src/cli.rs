@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "usage: game [--level <file> | --seed <number>] [--tuning <file>] \
+     [--bindings <file>] [--record <file>] [--replay <file> [--verify]] [--volume <0.0-1.0>] \
+     [--mute]";
+
+/// Used when there's no `--tuning`. It's fine if this doesn't exist.
+pub const DEFAULT_TUNING_PATH: &str = "tuning.toml";
+
+/// Used when there's no `--bindings`. Written with the default bindings if
+/// it doesn't exist yet, so there's something to edit.
+pub const DEFAULT_BINDINGS_PATH: &str = "bindings.toml";
+
+/// Command line options. Everything is optional, running with no arguments
+/// just starts the game.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    /// Level file to play, see `taxi_sim::level`.
+    pub level: Option<PathBuf>,
+    /// Play a generated city instead of a level file, see `taxi_sim::citygen`.
+    pub seed: Option<u64>,
+    /// Tuning file to load and watch for changes, see `taxi_sim::tuning`.
+    /// Defaults to `DEFAULT_TUNING_PATH`.
+    pub tuning: Option<PathBuf>,
+    /// Key and gamepad bindings, see `bindings.rs`. Defaults to
+    /// `DEFAULT_BINDINGS_PATH`.
+    pub bindings: Option<PathBuf>,
+    /// Write every simulation tick to this file.
+    pub record: Option<PathBuf>,
+    /// Play inputs back from this file instead of reading the keyboard.
+    pub replay: Option<PathBuf>,
+    /// With `replay`: run the whole thing straight away, check it matches the
+    /// recording and exit.
+    pub verify: bool,
+    /// Master volume, 0.0 to 1.0. Defaults to `audio::DEFAULT_VOLUME`.
+    pub volume: Option<f32>,
+    /// No sound at all, not even trying to open the audio device.
+    pub mute: bool,
+}
+
+pub fn parse_args(args: impl Iterator<Item = String>) -> Result<CliArgs, String> {
+    let mut cli_args = CliArgs::default();
+    let mut args = args.skip(1); // program name
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--level" => cli_args.level = Some(next_value(&mut args, &arg)?.into()),
+            "--seed" => {
+                let value = next_value(&mut args, &arg)?;
+                let seed = value
+                    .parse::<u64>()
+                    .map_err(|_| format!("--seed needs a whole number, not {}", value))?;
+                cli_args.seed = Some(seed);
+            }
+            "--tuning" => cli_args.tuning = Some(next_value(&mut args, &arg)?.into()),
+            "--bindings" => cli_args.bindings = Some(next_value(&mut args, &arg)?.into()),
+            "--record" => cli_args.record = Some(next_value(&mut args, &arg)?.into()),
+            "--replay" => cli_args.replay = Some(next_value(&mut args, &arg)?.into()),
+            "--verify" => cli_args.verify = true,
+            "--volume" => {
+                let value = next_value(&mut args, &arg)?;
+                let volume = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|volume| (0.0..=1.0).contains(volume))
+                    .ok_or_else(|| {
+                        format!("--volume needs a number from 0.0 to 1.0, not {}", value)
+                    })?;
+                cli_args.volume = Some(volume);
+            }
+            "--mute" => cli_args.mute = true,
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    if cli_args.verify && cli_args.replay.is_none() {
+        return Err("--verify needs a --replay file".to_owned());
+    }
+    if cli_args.level.is_some() && cli_args.replay.is_some() {
+        return Err("replays use the level they were recorded on, drop --level".to_owned());
+    }
+    if cli_args.seed.is_some() && cli_args.replay.is_some() {
+        return Err("replays use the city they were recorded in, drop --seed".to_owned());
+    }
+    if cli_args.level.is_some() && cli_args.seed.is_some() {
+        return Err("--level and --seed both pick the level, use one".to_owned());
+    }
+    if cli_args.tuning.is_some() && cli_args.replay.is_some() {
+        return Err("replays use the tuning they were recorded with, drop --tuning".to_owned());
+    }
+    if cli_args.record.is_some() && cli_args.replay.is_some() {
+        return Err("can't --record and --replay at the same time".to_owned());
+    }
+
+    Ok(cli_args)
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} needs a value", flag))
+}
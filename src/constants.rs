@@ -1,40 +1,24 @@
-pub const GAS_VELOCITY: f32 = 8.0;
-pub const CRAZY_DASH_VELOCITY: f32 = 30.0;
-pub const REVERSE_VELOCITY: f32 = 20.0;
-
-pub const CRAZY_DASH_LENGTH: f64 = 0.3;
-pub const CRAZY_DASH_MAX_VELOCITY: f32 = 2300.0;
-
-pub const PLAYER_MAX_VELOCITY: f32 = 750.0;
-//pub const PLAYER_MAX_VELOCITY: f32 = 500.0;
-pub const PLAYER_MAX_REVERSE_VELOCITY: f32 = -350.0;
-// Old rotation before improving turn behavior to be slower if not moving fast.
-//pub const PLAYER_ROTATION_SPEED: f32 = 125.0;
-pub const PLAYER_ROTATION_SPEED: f32 = 10.0;
-
-pub const CAR_DEFAULT_DRAG: f32 = 500.0;
-//pub const CRAZY_DASH_DRAG: f32 = 5.0;
-pub const CRAZY_DASH_DRAG: f32 = 50.0;
-pub const CRAZY_DASH_INPUT_TIMING: std::ops::Range<f64> = (0.04..0.09);
-
-//pub const CAMERA_SPEED: f32 = 2.5;
-pub const CAMERA_SPEED: f32 = 7.5;
-
 /// Will render debug info if true (includes grid right now)
 pub const IS_DEBUG: bool = true;
 
-// this is extra but was trying to improve the function where vertices are created
-// TODO could revert this tbh
-pub struct PlayerShapeConfig {
-    pub half_width: f32,
-    pub half_height: f32,
-    pub inner_space_w: f32,
-    pub inner_space_h: f32,
-}
-
-pub const PLAYER_SHAPE: PlayerShapeConfig = PlayerShapeConfig {
-    half_width: 30.0,
-    half_height: 40.0,
-    inner_space_w: 10.0,
-    inner_space_h: 15.0,
-};
+// How quickly the camera catches up to the taxi.
+//pub const CAMERA_SPEED: f32 = 2.5;
+pub const CAMERA_SPEED: f32 = 7.5;
+/// Seconds of the taxi's velocity the camera looks ahead by...
+pub const CAMERA_LOOK_AHEAD: f32 = 0.35;
+/// ...but never further than this.
+pub const CAMERA_MAX_LOOK_AHEAD: f32 = 400.0;
+/// The camera doesn't bother moving until its target is this far away.
+pub const CAMERA_DEAD_ZONE: f32 = 40.0;
+/// Zoom at `CRAZY_DASH_MAX_VELOCITY`; 1.0 is no zoom, smaller shows more.
+pub const CAMERA_MIN_ZOOM: f32 = 0.55;
+/// How quickly the zoom catches up to the speed.
+pub const CAMERA_ZOOM_RATE: f32 = 2.0;
+/// Furthest the screen moves when shaking as hard as it can, in pixels.
+pub const CAMERA_SHAKE_MAX_OFFSET: f32 = 25.0;
+/// How much shake wears off per second (1.0 is the most shake there is).
+pub const CAMERA_SHAKE_DECAY: f32 = 1.5;
+/// Impact speed that shakes the screen as hard as it can.
+pub const CAMERA_SHAKE_IMPACT_SPEED: f32 = 800.0;
+/// Shake when a crazy dash starts.
+pub const CAMERA_DASH_SHAKE: f32 = 0.35;
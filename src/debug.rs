@@ -1,6 +1,8 @@
-use crate::math::{Point, convert_world_pos_to_camera_pos};
+use crate::camera::Camera;
 use macroquad::color::*;
 use macroquad::prelude::*;
+use taxi_sim::constants::TILE_SIZE;
+use taxi_sim::math::Point;
 
 /// this is really extra but it works
 #[derive(Clone, Copy)]
@@ -9,6 +11,9 @@ pub struct DebugState {
     pub show_constants: bool,
     pub show_grid: bool,
     pub show_crazy_dash_indicator: bool,
+    pub show_tuning_panel: bool,
+    /// Outlines and dots instead of textures.
+    pub wireframe: bool,
 }
 
 impl DebugState {
@@ -18,6 +23,8 @@ impl DebugState {
             show_constants: true,
             show_grid: true,
             show_crazy_dash_indicator: true,
+            show_tuning_panel: false,
+            wireframe: false,
         }
     }
 
@@ -36,6 +43,20 @@ impl DebugState {
     pub fn toggle_crazy_dash_indicator(&mut self) {
         self.show_crazy_dash_indicator = !self.show_crazy_dash_indicator;
     }
+
+    pub fn toggle_tuning_panel(&mut self) {
+        self.show_tuning_panel = !self.show_tuning_panel;
+    }
+
+    pub fn toggle_wireframe(&mut self) {
+        self.wireframe = !self.wireframe;
+    }
+}
+
+impl Default for DebugState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Just a convenient function/struct I can call when I need to add
@@ -96,6 +117,14 @@ impl DebugRenderer {
         self.debug_state.toggle_crazy_dash_indicator();
     }
 
+    pub fn toggle_tuning_panel(&mut self) {
+        self.debug_state.toggle_tuning_panel();
+    }
+
+    pub fn toggle_wireframe(&mut self) {
+        self.debug_state.toggle_wireframe();
+    }
+
     pub fn add_text(&mut self, text: &str) {
         if !self.debug_state.show_text {
             return;
@@ -136,69 +165,65 @@ impl DebugRenderer {
     }
 }
 
-pub fn render_grid(camera: &Rect) {
-    let grid_size = 100.0;
+impl Default for DebugRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lines up with the tilemap's tiles.
+pub fn render_grid(camera: &Camera) {
+    let grid_size = TILE_SIZE;
+    let view = camera.view_rect();
 
-    // Get start/end of camera rect
-    let start_x = (camera.x / grid_size).floor() * grid_size;
-    let start_y = (camera.y / grid_size).floor() * grid_size;
-    let end_x = start_x + camera.w + grid_size;
-    let end_y = start_y + camera.h + grid_size;
+    // Get start/end of the visible area
+    let start_x = (view.x / grid_size).floor() * grid_size;
+    let start_y = (view.y / grid_size).floor() * grid_size;
+    let end_x = view.x + view.w;
+    let end_y = view.y + view.h;
 
     // Draw vertical grid lines
     let mut world_x = start_x;
     while world_x <= end_x {
-        let line_top = Point::new(world_x, camera.y);
-        let line_bottom = Point::new(world_x, camera.y + camera.h);
-
-        let camera_top = convert_world_pos_to_camera_pos(&line_top, camera);
-        let camera_bottom = convert_world_pos_to_camera_pos(&line_bottom, camera);
-
-        // Ignore if line isn't in camera view
-        if camera_top.x >= 0.0 && camera_top.x <= camera.w {
-            draw_line(
-                camera_top.x,
-                camera_top.y,
-                camera_bottom.x,
-                camera_bottom.y,
-                1.0,
-                Color::from_rgba(40, 40, 40, 255),
-            );
-        }
+        let screen_top = camera.world_to_screen(&Point::new(world_x, view.y));
+        let screen_bottom = camera.world_to_screen(&Point::new(world_x, end_y));
+
+        draw_line(
+            screen_top.x,
+            screen_top.y,
+            screen_bottom.x,
+            screen_bottom.y,
+            1.0,
+            Color::from_rgba(40, 40, 40, 255),
+        );
         world_x += grid_size;
     }
 
     // Draw horizontal grid lines
     let mut world_y = start_y;
     while world_y <= end_y {
-        let line_left = Point::new(camera.x, world_y);
-        let line_right = Point::new(camera.x + camera.w, world_y);
-
-        let screen_left = convert_world_pos_to_camera_pos(&line_left, camera);
-        let screen_right = convert_world_pos_to_camera_pos(&line_right, camera);
-
-        // Ignore if line isn't in camera view
-        if screen_left.y >= 0.0 && screen_left.y <= camera.h {
-            draw_line(
-                screen_left.x,
-                screen_left.y,
-                screen_right.x,
-                screen_right.y,
-                1.0,
-                Color::from_rgba(40, 40, 40, 255),
-            );
-        }
+        let screen_left = camera.world_to_screen(&Point::new(view.x, world_y));
+        let screen_right = camera.world_to_screen(&Point::new(end_x, world_y));
+
+        draw_line(
+            screen_left.x,
+            screen_left.y,
+            screen_right.x,
+            screen_right.y,
+            1.0,
+            Color::from_rgba(40, 40, 40, 255),
+        );
         world_y += grid_size;
     }
 
     let origin_world = Point::new(0.0, 0.0);
-    let origin_camera_pos = convert_world_pos_to_camera_pos(&origin_world, camera);
+    let origin_camera_pos = camera.world_to_screen(&origin_world);
 
     // Only draw origin if in camera view
     if origin_camera_pos.x >= -20.0
-        && origin_camera_pos.x <= camera.w + 20.0
+        && origin_camera_pos.x <= screen_width() + 20.0
         && origin_camera_pos.y >= -20.0
-        && origin_camera_pos.y <= camera.h + 20.0
+        && origin_camera_pos.y <= screen_height() + 20.0
     {
         draw_circle(origin_camera_pos.x, origin_camera_pos.y, 10.0, RED);
         draw_text(
@@ -0,0 +1,79 @@
+use taxi_sim::events::GameEvent;
+use taxi_sim::math::Point;
+
+/// How long the taxi flashes after a hit, in seconds.
+const HIT_FLASH_TIME: f32 = 0.25;
+/// Impact speed (see `GameEvent::Collision`) that gives the brightest flash.
+const HIT_FLASH_IMPACT_SPEED: f32 = 600.0;
+/// How long score pop-ups stay up, in seconds.
+pub const SCORE_POPUP_TIME: f32 = 1.2;
+
+/// Points for a trick, shown where it happened.
+pub struct ScorePopup {
+    pub text: String,
+    /// World space.
+    pub position: Point,
+    /// Seconds since it popped up.
+    pub age: f32,
+}
+
+/// Visual effects on the taxi, driven by the simulation's events rather than
+/// looking at the taxi every frame to see what changed.
+pub struct Effects {
+    /// Between a `DashStarted` and its `DashEnded`.
+    pub taxi_dashing: bool,
+    /// 0.0 to 1.0, how white the taxi flashes from the last hit.
+    pub hit_flash: f32,
+    pub score_popups: Vec<ScorePopup>,
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        Self {
+            taxi_dashing: false,
+            hit_flash: 0.0,
+            score_popups: Vec::new(),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::DashStarted { .. } => self.taxi_dashing = true,
+            GameEvent::DashEnded => self.taxi_dashing = false,
+            GameEvent::Collision { impact_speed, .. } => {
+                let flash = (impact_speed / HIT_FLASH_IMPACT_SPEED).min(1.0);
+                self.hit_flash = self.hit_flash.max(flash);
+            }
+            GameEvent::Wrecked => self.hit_flash = 1.0,
+            GameEvent::Scored {
+                trick,
+                points,
+                combo,
+                position,
+            } => {
+                let text = if *combo > 1.0 {
+                    format!("{} +{} (x{:.1})", trick.name(), points, combo)
+                } else {
+                    format!("{} +{}", trick.name(), points)
+                };
+                self.score_popups.push(ScorePopup {
+                    text,
+                    position: *position,
+                    age: 0.0,
+                });
+            }
+            _ => (),
+        }
+    }
+
+    /// Every rendered frame, so flashes fade out smoothly.
+    pub fn update(&mut self, delta_time: f32) {
+        self.hit_flash = (self.hit_flash - delta_time / HIT_FLASH_TIME).max(0.0);
+
+        for popup in self.score_popups.iter_mut() {
+            popup.age += delta_time;
+        }
+        self.score_popups
+            .retain(|popup| popup.age < SCORE_POPUP_TIME);
+    }
+}
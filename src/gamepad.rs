@@ -0,0 +1,105 @@
+//! Gamepad input through gilrs, behind the `gamepad` feature. Without it
+//! `Gamepads::new` just returns `None`, so the rest of the game doesn't need
+//! to care whether it was built in.
+
+use crate::bindings::{PadAxis, PadButton};
+
+#[cfg(feature = "gamepad")]
+pub struct Gamepads {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepads {
+    /// `None` if there's no way to read gamepads on this machine.
+    pub fn new() -> Option<Self> {
+        match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(Self { gilrs }),
+            Err(error) => {
+                eprintln!("gamepads disabled: {}", error);
+                None
+            }
+        }
+    }
+
+    /// Call once a frame before reading anything.
+    pub fn update(&mut self) {
+        // gilrs only updates its state as events are read.
+        while self.gilrs.next_event().is_some() {}
+    }
+
+    /// Pressed on any connected gamepad.
+    pub fn is_pressed(&self, button: PadButton) -> bool {
+        let button = gilrs_button(button);
+        self.gilrs
+            .gamepads()
+            .any(|(_, gamepad)| gamepad.is_pressed(button))
+    }
+
+    /// Whichever connected gamepad has the stick pushed furthest.
+    pub fn axis(&self, axis: PadAxis) -> f32 {
+        let axis = gilrs_axis(axis);
+        self.gilrs
+            .gamepads()
+            .map(|(_, gamepad)| gamepad.value(axis))
+            .fold(0.0, |furthest, value| {
+                if value.abs() > f32::abs(furthest) {
+                    value
+                } else {
+                    furthest
+                }
+            })
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn gilrs_button(button: PadButton) -> gilrs::Button {
+    match button {
+        PadButton::South => gilrs::Button::South,
+        PadButton::East => gilrs::Button::East,
+        PadButton::North => gilrs::Button::North,
+        PadButton::West => gilrs::Button::West,
+        PadButton::LeftTrigger => gilrs::Button::LeftTrigger,
+        PadButton::LeftTrigger2 => gilrs::Button::LeftTrigger2,
+        PadButton::RightTrigger => gilrs::Button::RightTrigger,
+        PadButton::RightTrigger2 => gilrs::Button::RightTrigger2,
+        PadButton::Select => gilrs::Button::Select,
+        PadButton::Start => gilrs::Button::Start,
+        PadButton::LeftThumb => gilrs::Button::LeftThumb,
+        PadButton::RightThumb => gilrs::Button::RightThumb,
+        PadButton::DPadUp => gilrs::Button::DPadUp,
+        PadButton::DPadDown => gilrs::Button::DPadDown,
+        PadButton::DPadLeft => gilrs::Button::DPadLeft,
+        PadButton::DPadRight => gilrs::Button::DPadRight,
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn gilrs_axis(axis: PadAxis) -> gilrs::Axis {
+    match axis {
+        PadAxis::LeftStickX => gilrs::Axis::LeftStickX,
+        PadAxis::LeftStickY => gilrs::Axis::LeftStickY,
+        PadAxis::RightStickX => gilrs::Axis::RightStickX,
+        PadAxis::RightStickY => gilrs::Axis::RightStickY,
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+pub struct Gamepads;
+
+#[cfg(not(feature = "gamepad"))]
+impl Gamepads {
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    pub fn update(&mut self) {}
+
+    pub fn is_pressed(&self, _button: PadButton) -> bool {
+        false
+    }
+
+    pub fn axis(&self, _axis: PadAxis) -> f32 {
+        0.0
+    }
+}
@@ -1,84 +1,372 @@
-use crate::player::PlayerAction;
+use crate::bindings::{Bindings, Control};
+use crate::camera::Camera;
 use crate::debug::DebugRenderer;
-use macroquad::input::is_key_pressed;
+use crate::gamepad::Gamepads;
 use macroquad::input::*;
+use macroquad::ui::root_ui;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use taxi_sim::input::InputFrame;
+use taxi_sim::math::Point;
+use taxi_sim::player::PlayerAction;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
 pub enum DebugAction {
     ToggleText,
     ToggleConstants,
     ToggleGrid,
     ToggleCrazyDashIndicator,
+    ToggleTuningPanel,
+    ToggleWireframe,
 }
 
-//use macroquad:#[derive(Debug, Clone)]
-pub struct InputFrame {
-    pub player_actions: Vec<PlayerAction>,
-    pub debug_actions: Vec<DebugAction>,
+/// Getting around the title screen, pause menu and results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuAction {
+    Up,
+    Down,
+    Confirm,
+    /// Pauses while driving, and resumes again from the pause menu.
+    Pause,
 }
 
-impl InputFrame {
-    pub fn new() -> Self {
+const MENU_ACTIONS: [MenuAction; 4] = [
+    MenuAction::Up,
+    MenuAction::Down,
+    MenuAction::Confirm,
+    MenuAction::Pause,
+];
+
+const CONTROLS: [Control; 8] = [
+    Control::Gas,
+    Control::Brake,
+    Control::Drive,
+    Control::Reverse,
+    Control::Neutral,
+    Control::Park,
+    Control::SteerLeft,
+    Control::SteerRight,
+];
+
+/// Turns the keyboard and gamepads into `PlayerAction`s, going through
+/// `Bindings`.
+pub struct Input {
+    pub bindings: Bindings,
+    gamepads: Option<Gamepads>,
+    /// Controls held last frame, to tell which ones were just pressed.
+    held_last_frame: HashSet<Control>,
+    menu_held_last_frame: HashSet<MenuAction>,
+    /// Most recently pressed of left/right and drive/reverse, which wins if
+    /// both are held.
+    last_steer: Control,
+    last_shift: Control,
+}
+
+impl Input {
+    pub fn new(bindings: Bindings) -> Self {
         Self {
-            player_actions: Vec::new(),
-            debug_actions: Vec::new(),
+            bindings,
+            gamepads: Gamepads::new(),
+            held_last_frame: HashSet::new(),
+            menu_held_last_frame: HashSet::new(),
+            last_steer: Control::SteerLeft,
+            last_shift: Control::Drive,
         }
     }
-}
 
-// NOTE: this can be improved but leaving as is right now.
-// Need to improve the logic to be smart about what actions its adding to the
-// input frame (can't press left & right, etc.)
-pub fn process_inputs(input_frame: &mut InputFrame) {
-    input_frame.player_actions.clear();
-    input_frame.debug_actions.clear();
-
-    // Define key mappings
-    let key_mappings = [
-        (KeyCode::Up, PlayerAction::ShiftIntoDrive),
-        (KeyCode::Down, PlayerAction::ShiftIntoReverse),
-        (KeyCode::Space, PlayerAction::GasHeld),
-        (KeyCode::Left, PlayerAction::TurnLeft),
-        (KeyCode::Right, PlayerAction::TurnRight),
-    ];
-
-    // Process keyboard inputs
-    for (key, action) in key_mappings {
-        if is_key_down(key) {
-            input_frame.player_actions.push(action);
+    /// Call once a frame, before processing any input.
+    pub fn update_gamepads(&mut self) {
+        if let Some(gamepads) = self.gamepads.as_mut() {
+            gamepads.update();
         }
     }
 
-    // Specifically for crazy dashing
-    if is_key_pressed(KeyCode::Space) {
-        input_frame.player_actions.push(PlayerAction::GasActivated);
+    /// Menu actions pressed since last frame.
+    pub fn process_menu_inputs(&mut self) -> Vec<MenuAction> {
+        let held: HashSet<MenuAction> = MENU_ACTIONS
+            .into_iter()
+            .filter(|menu_action| self.is_menu_action_held(*menu_action))
+            .collect();
+        let pressed = MENU_ACTIONS
+            .into_iter()
+            .filter(|menu_action| {
+                held.contains(menu_action) && !self.menu_held_last_frame.contains(menu_action)
+            })
+            .collect();
+
+        self.menu_held_last_frame = held;
+        pressed
+    }
+
+    /// Treats whatever's held right now as held since before, so coming back
+    /// to driving from a menu doesn't count the key that closed the menu
+    /// (often the same one as the gas) as a fresh press.
+    pub fn skip_presses(&mut self) {
+        self.held_last_frame = CONTROLS
+            .into_iter()
+            .filter(|control| self.is_held(*control))
+            .collect();
     }
 
-    // convenient toggles for debug info
-    let debug_key_mappings = [
-        (KeyCode::Key1, DebugAction::ToggleText),
-        (KeyCode::Key2, DebugAction::ToggleConstants),
-        (KeyCode::Key3, DebugAction::ToggleCrazyDashIndicator),
-        (KeyCode::Key4, DebugAction::ToggleGrid),
-    ];
+    pub fn process_inputs(&mut self, input_frame: &mut InputFrame, camera: &Camera) {
+        // One shot actions stay queued until a simulation tick has used them
+        // (a frame doesn't always run a tick).
+        input_frame
+            .player_actions
+            .retain(|action| action.is_one_shot());
+
+        let held: HashSet<Control> = CONTROLS
+            .into_iter()
+            .filter(|control| self.is_held(*control))
+            .collect();
+        let pressed: HashSet<Control> = held.difference(&self.held_last_frame).copied().collect();
+
+        // Can't sensibly go both ways at once, so when both of a pair are
+        // held, whichever was pressed last wins.
+        let shift = resolve_conflict(
+            &held,
+            &pressed,
+            (Control::Drive, Control::Reverse),
+            &mut self.last_shift,
+        );
+        match shift {
+            Some(Control::Drive) => input_frame
+                .player_actions
+                .push(PlayerAction::ShiftIntoDrive),
+            Some(_) => input_frame
+                .player_actions
+                .push(PlayerAction::ShiftIntoReverse),
+            None => (),
+        }
+
+        let held_actions = [
+            (Control::Neutral, PlayerAction::ShiftIntoNeutral),
+            (Control::Park, PlayerAction::ShiftIntoPark),
+            (Control::Brake, PlayerAction::Brake),
+            (Control::Gas, PlayerAction::GasHeld),
+        ];
+        for (control, action) in held_actions {
+            if held.contains(&control) {
+                input_frame.player_actions.push(action);
+            }
+        }
+
+        let steer = self.steer(&held, &pressed);
+        if steer != 0.0 {
+            input_frame.player_actions.push(PlayerAction::Steer(steer));
+        }
+
+        // Specifically for crazy dashing
+        if pressed.contains(&Control::Gas) {
+            input_frame.player_actions.push(PlayerAction::GasActivated);
+        }
 
-    for (key, action) in debug_key_mappings {
-        if is_key_pressed(key) {
-            input_frame.debug_actions.push(action);
+        if let Some(action) = teleport_action(camera) {
+            input_frame.player_actions.push(action);
         }
+
+        self.held_last_frame = held;
     }
-}
 
-pub fn process_debug_inputs(input_frame: &InputFrame, debug_renderer: &mut DebugRenderer) {
-    // Process debug toggles
-    for debug_action in input_frame.debug_actions.iter() {
-        match debug_action {
-            DebugAction::ToggleText => debug_renderer.toggle_text(),
-            DebugAction::ToggleConstants => debug_renderer.toggle_constants(),
-            DebugAction::ToggleGrid => debug_renderer.toggle_grid(),
-            DebugAction::ToggleCrazyDashIndicator => {
-                debug_renderer.toggle_crazy_dash_indicator()
+    pub fn process_debug_inputs(&self, debug_renderer: &mut DebugRenderer) {
+        // convenient toggles for debug info
+        for (debug_action, keys) in self.bindings.debug_keys.iter() {
+            if !keys.iter().any(|key| is_key_pressed(key.0)) {
+                continue;
+            }
+
+            match debug_action {
+                DebugAction::ToggleText => debug_renderer.toggle_text(),
+                DebugAction::ToggleConstants => debug_renderer.toggle_constants(),
+                DebugAction::ToggleGrid => debug_renderer.toggle_grid(),
+                DebugAction::ToggleTuningPanel => debug_renderer.toggle_tuning_panel(),
+                DebugAction::ToggleWireframe => debug_renderer.toggle_wireframe(),
+                DebugAction::ToggleCrazyDashIndicator => {
+                    debug_renderer.toggle_crazy_dash_indicator()
+                }
             }
         }
     }
+
+    fn is_held(&self, control: Control) -> bool {
+        let key_held = self
+            .bindings
+            .keys
+            .get(&control)
+            .is_some_and(|keys| keys.iter().any(|key| is_key_down(key.0)));
+        let button_held = self.gamepads.as_ref().is_some_and(|gamepads| {
+            self.bindings
+                .gamepad_buttons
+                .get(&control)
+                .is_some_and(|buttons| buttons.iter().any(|button| gamepads.is_pressed(*button)))
+        });
+
+        key_held || button_held
+    }
+
+    fn is_menu_action_held(&self, menu_action: MenuAction) -> bool {
+        let key_held = self
+            .bindings
+            .menu_keys
+            .get(&menu_action)
+            .is_some_and(|keys| keys.iter().any(|key| is_key_down(key.0)));
+        let button_held = self.gamepads.as_ref().is_some_and(|gamepads| {
+            self.bindings
+                .menu_buttons
+                .get(&menu_action)
+                .is_some_and(|buttons| buttons.iter().any(|button| gamepads.is_pressed(*button)))
+        });
+
+        key_held || button_held
+    }
+
+    /// -1.0 (full left) to 1.0 (full right). Keys and the d-pad are full
+    /// lock; the stick is used instead if it's pushed further.
+    fn steer(&mut self, held: &HashSet<Control>, pressed: &HashSet<Control>) -> f32 {
+        let digital = match resolve_conflict(
+            held,
+            pressed,
+            (Control::SteerLeft, Control::SteerRight),
+            &mut self.last_steer,
+        ) {
+            Some(Control::SteerLeft) => -1.0,
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+
+        let analog = match (self.gamepads.as_ref(), self.bindings.steer_axis) {
+            (Some(gamepads), Some(axis)) => {
+                apply_dead_zone(gamepads.axis(axis), self.bindings.steer_dead_zone)
+            }
+            _ => 0.0,
+        };
+
+        if analog.abs() > f32::abs(digital) {
+            analog
+        } else {
+            digital
+        }
+    }
+}
+
+/// Left click teleports the taxi there as it is, right click also stops it
+/// and turns it to face up. Handy for trying out bits of a level.
+fn teleport_action(camera: &Camera) -> Option<PlayerAction> {
+    let reset_motion = if is_mouse_button_pressed(MouseButton::Left) {
+        false
+    } else if is_mouse_button_pressed(MouseButton::Right) {
+        true
+    } else {
+        return None;
+    };
+
+    // Clicks on the tuning panel are for the panel.
+    let (mouse_x, mouse_y) = mouse_position();
+    if root_ui().is_mouse_over(macroquad::math::vec2(mouse_x, mouse_y)) {
+        return None;
+    }
+
+    let world_pos = camera.screen_to_world(&Point::new(mouse_x, mouse_y));
+    Some(PlayerAction::Reposition {
+        x: world_pos.x,
+        y: world_pos.y,
+        reset_motion,
+    })
+}
+
+/// Which of two opposite controls to go with, if either is held. `last` is
+/// the most recently pressed of the two.
+fn resolve_conflict(
+    held: &HashSet<Control>,
+    pressed: &HashSet<Control>,
+    (a, b): (Control, Control),
+    last: &mut Control,
+) -> Option<Control> {
+    if pressed.contains(&a) {
+        *last = a;
+    }
+    if pressed.contains(&b) {
+        *last = b;
+    }
+
+    match (held.contains(&a), held.contains(&b)) {
+        (true, true) => Some(*last),
+        (true, false) => Some(a),
+        (false, true) => Some(b),
+        (false, false) => None,
+    }
+}
+
+/// Ignores small stick movements, and rescales the rest so just past the
+/// dead zone is still a gentle turn rather than a jump.
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value.abs() <= dead_zone {
+        return 0.0;
+    }
+    value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEERING: (Control, Control) = (Control::SteerLeft, Control::SteerRight);
+
+    fn controls(controls: &[Control]) -> HashSet<Control> {
+        controls.iter().copied().collect()
+    }
+
+    #[test]
+    fn one_held_control_wins() {
+        let mut last = Control::SteerRight;
+        let held = controls(&[Control::SteerLeft]);
+
+        assert_eq!(
+            resolve_conflict(&held, &controls(&[]), STEERING, &mut last),
+            Some(Control::SteerLeft)
+        );
+        assert_eq!(
+            resolve_conflict(&controls(&[]), &controls(&[]), STEERING, &mut last),
+            None
+        );
+    }
+
+    #[test]
+    fn both_held_goes_with_the_last_pressed() {
+        let mut last = Control::SteerLeft;
+        let both = controls(&[Control::SteerLeft, Control::SteerRight]);
+
+        // Holding left, then right goes down too.
+        let pressed = controls(&[Control::SteerRight]);
+        assert_eq!(
+            resolve_conflict(&both, &pressed, STEERING, &mut last),
+            Some(Control::SteerRight)
+        );
+        // Still both held, nothing new pressed.
+        assert_eq!(
+            resolve_conflict(&both, &controls(&[]), STEERING, &mut last),
+            Some(Control::SteerRight)
+        );
+        // Let go of right, so back to left.
+        let held = controls(&[Control::SteerLeft]);
+        assert_eq!(
+            resolve_conflict(&held, &controls(&[]), STEERING, &mut last),
+            Some(Control::SteerLeft)
+        );
+    }
+
+    #[test]
+    fn dead_zone_is_ignored_and_the_rest_rescaled() {
+        assert_eq!(apply_dead_zone(0.1, 0.2), 0.0);
+        assert_eq!(apply_dead_zone(-0.2, 0.2), 0.0);
+        assert!((apply_dead_zone(0.6, 0.2) - 0.5).abs() < 1e-6);
+        assert!((apply_dead_zone(-0.6, 0.2) + 0.5).abs() < 1e-6);
+        assert_eq!(apply_dead_zone(1.0, 0.2), 1.0);
+        assert_eq!(apply_dead_zone(-1.0, 0.2), -1.0);
+        // No dead zone leaves it alone.
+        assert_eq!(apply_dead_zone(0.3, 0.0), 0.3);
+    }
 }
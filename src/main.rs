@@ -1,22 +1,48 @@
 use macroquad::prelude::*;
 use macroquad::time::get_frame_time;
+use std::process::exit;
 
+mod assets;
+mod audio;
+mod bindings;
+mod camera;
+mod cli;
 mod constants;
 mod debug;
-mod gamestate;
+mod effects;
+mod gamepad;
 mod input;
-mod math;
-mod player;
 mod renderer;
-mod wall;
+mod scene;
+#[cfg(feature = "audio")]
+mod synth;
+mod tuning_panel;
 
+use assets::{Assets, MANIFEST_PATH};
+use audio::{Audio, DEFAULT_VOLUME};
+use bindings::Bindings;
+use camera::{Camera, CameraTuning};
+use cli::{CliArgs, DEFAULT_BINDINGS_PATH, DEFAULT_TUNING_PATH, USAGE, parse_args};
+use constants::IS_DEBUG;
 use debug::DebugRenderer;
-use gamestate::simulate;
-use gamestate::*;
+use effects::Effects;
 use input::*;
-use player::Player;
 use renderer::render;
-use wall::Wall;
+use scene::{MenuItem, Scene, Scenes};
+use std::path::Path;
+use taxi_sim::citygen::generate_city;
+use taxi_sim::constants::SIMULATION_TICK_RATE;
+use taxi_sim::gamestate::simulate;
+use taxi_sim::gamestate::*;
+use taxi_sim::input::InputFrame;
+use taxi_sim::level::Level;
+use taxi_sim::math::Point;
+use taxi_sim::player::Player;
+use taxi_sim::replay::{Replay, ReplayHeader, ReplayRecorder};
+use taxi_sim::stats::Stats;
+use taxi_sim::timestep::FixedTimestep;
+use taxi_sim::tuning::TuningWatcher;
+use tuning_panel::TuningPanel;
 
 fn window_conf() -> Conf {
     Conf {
@@ -31,23 +57,298 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
+    let cli_args = parse_args(std::env::args()).unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        exit(2);
+    });
+
+    let replay = cli_args.replay.as_ref().map(|path| {
+        Replay::load(path).unwrap_or_else(|error| {
+            eprintln!("{}: {}", path.display(), error);
+            exit(1);
+        })
+    });
+
+    let mut game_state = load_game_state(&cli_args, replay.as_ref());
+
+    // Replays bring their own tuning, apart from the camera's which isn't
+    // recorded.
+    let tuning_path = cli_args
+        .tuning
+        .clone()
+        .unwrap_or_else(|| DEFAULT_TUNING_PATH.into());
+    let mut camera_tuning = CameraTuning::default();
+    if tuning_path.exists() {
+        reload_camera_tuning(&tuning_path, &mut camera_tuning);
+    }
+    let mut tuning_watcher = replay.is_none().then(|| TuningWatcher::new(&tuning_path));
+    if let Some(tuning_watcher) = tuning_watcher.as_mut() {
+        match tuning_watcher.poll() {
+            Some(Ok(tuning)) => game_state.set_tuning(tuning),
+            Some(Err(error)) => {
+                eprintln!("{}: {}", tuning_watcher.path.display(), error);
+                exit(1);
+            }
+            None if cli_args.tuning.is_some() => {
+                eprintln!("{}: file not found", tuning_watcher.path.display());
+                exit(1);
+            }
+            None => (),
+        }
+    }
+    // Replays only store the tuning from the start, so changing it partway
+    // through a recording would make it play back differently.
+    if cli_args.record.is_some() {
+        tuning_watcher = None;
+    }
+
+    if let Some(replay) = replay.as_ref().filter(|_| cli_args.verify) {
+        match replay.verify(game_state) {
+            Ok(_) => {
+                println!("replay matches ({} ticks)", replay.ticks.len());
+                exit(0);
+            }
+            Err(mismatch) => {
+                eprintln!("{}", mismatch);
+                exit(1);
+            }
+        }
+    }
+
+    let assets = Assets::load(MANIFEST_PATH).await;
+    let mut input = Input::new(load_bindings(&cli_args));
     let mut input_frame = InputFrame::new();
-    // FIXME: have player instantiated in gamestate, shouldn't be here.
-    let player = Player::new(screen_width() / 2.0, 120.0);
-    let mut game_state = GameState::new(player);
     let mut debug_renderer = DebugRenderer::new();
-    let mut camera = Rect::new(0.0, 0.0, screen_width(), screen_height());
+    let mut tuning_panel = TuningPanel::new(replay.is_some() || cli_args.record.is_some());
+    let mut camera = Camera::new(
+        game_state.player.center,
+        Point::new(screen_width(), screen_height()),
+        GameState::world_bounds(),
+    );
+    let mut timestep = FixedTimestep::new(SIMULATION_TICK_RATE);
+    let mut effects = Effects::new();
+    let mut audio = Audio::new(cli_args.volume.unwrap_or(DEFAULT_VOLUME), cli_args.mute).await;
+    let mut stats = Stats::new();
+
+    let mut recorder = cli_args
+        .record
+        .as_ref()
+        .map(|path| start_recording(path, &cli_args, &game_state));
+    let mut replay_ticks = replay.as_ref().map(|replay| replay.ticks.iter());
+    // Replays skip the title screen and just start playing.
+    let mut scenes = Scenes::new(match replay {
+        Some(_) => Scene::Playing,
+        None => Scene::Title,
+    });
 
     loop {
-        let delta_time = get_frame_time();
-        process_inputs(&mut input_frame);
+        let frame_time = get_frame_time();
+        input.update_gamepads();
+        input.process_debug_inputs(&mut debug_renderer);
+
+        let scene_before = scenes.current;
+        for menu_action in input.process_menu_inputs() {
+            let Some(item) = scenes.handle_menu_action(menu_action) else {
+                continue;
+            };
+            match item {
+                MenuItem::Start | MenuItem::Resume => scenes.switch_to(Scene::Playing),
+                MenuItem::Restart => {
+                    // Keep any tuning changes made since starting. Replays
+                    // have to start over with their own tuning to match.
+                    let tuning = game_state.player.tuning.clone();
+                    game_state = load_game_state(&cli_args, replay.as_ref());
+                    if replay.is_none() {
+                        game_state.set_tuning(tuning);
+                    }
+
+                    input_frame = InputFrame::new();
+                    camera = Camera::new(
+                        game_state.player.center,
+                        Point::new(screen_width(), screen_height()),
+                        GameState::world_bounds(),
+                    );
+                    timestep = FixedTimestep::new(SIMULATION_TICK_RATE);
+                    effects = Effects::new();
+                    stats = Stats::new();
+                    // The recording starts over too, so it still matches
+                    // the game from the start.
+                    recorder = cli_args
+                        .record
+                        .as_ref()
+                        .map(|path| start_recording(path, &cli_args, &game_state));
+                    replay_ticks = replay.as_ref().map(|replay| replay.ticks.iter());
+                    scenes.switch_to(Scene::Playing);
+                }
+                MenuItem::EndShift => scenes.switch_to(Scene::Results),
+                MenuItem::Quit => return,
+            }
+        }
+        let playing = scenes.current == Scene::Playing;
+        if playing && scene_before != Scene::Playing {
+            input.skip_presses();
+        }
+
+        if let Some(tuning_watcher) = tuning_watcher.as_mut() {
+            match tuning_watcher.poll() {
+                Some(Ok(tuning)) => {
+                    game_state.set_tuning(tuning);
+                    reload_camera_tuning(&tuning_watcher.path, &mut camera_tuning);
+                }
+                // Keep driving with the old tuning until the file is fixed.
+                Some(Err(error)) => eprintln!("{}: {}", tuning_watcher.path.display(), error),
+                None => (),
+            }
+        }
+
+        if IS_DEBUG && debug_renderer.debug_state.show_tuning_panel {
+            let mut tuning = game_state.player.tuning.clone();
+            if !tuning_panel.draw(&mut tuning, &mut camera_tuning) {
+                debug_renderer.toggle_tuning_panel();
+            }
+            if tuning != game_state.player.tuning {
+                game_state.set_tuning(tuning);
+            }
+        }
+
+        // Nothing moves unless we're driving, so the sim clock stops too.
+        if playing {
+            input.process_inputs(&mut input_frame, &camera);
+            for _ in 0..timestep.advance(frame_time) {
+                if let Some(replay_ticks) = replay_ticks.as_mut() {
+                    // Replays ignore the keyboard and just run out once every
+                    // recorded tick has been played.
+                    let Some(tick) = replay_ticks.next() else {
+                        continue;
+                    };
+                    simulate(&tick.input_frame(), &mut game_state, tick.delta_time);
+                } else {
+                    simulate(&input_frame, &mut game_state, timestep.tick_delta_time);
+
+                    let record_result = recorder.as_mut().map(|recorder| {
+                        recorder.record(&input_frame, timestep.tick_delta_time, &game_state)
+                    });
+                    if let Some(Err(error)) = record_result {
+                        eprintln!("stopped recording: {}", error);
+                        recorder = None;
+                    }
+
+                    input_frame.remove_one_shot_actions();
+                }
 
-        process_debug_inputs(&input_frame, &mut debug_renderer);
+                for event in game_state.events.iter() {
+                    camera.handle_event(event, &camera_tuning);
+                    effects.handle_event(event);
+                    audio.handle_event(event);
+                    stats.record(event);
+                }
 
-        simulate(&input_frame, &mut game_state, &mut camera, delta_time);
+                if game_state.is_shift_over() {
+                    break;
+                }
+            }
 
-        render(&game_state, &camera, &mut debug_renderer);
+            if game_state.is_shift_over() {
+                scenes.switch_to(Scene::Results);
+            }
+        }
+
+        let alpha = timestep.alpha();
+        camera.screen_size = Point::new(screen_width(), screen_height());
+        if playing {
+            camera.update(
+                &game_state.player.interpolated_center(alpha),
+                &game_state.player.velocity,
+                game_state.player.tuning.crazy_dash_max_velocity,
+                frame_time,
+                &camera_tuning,
+            );
+            effects.update(frame_time);
+            audio.update(&game_state.player);
+        } else {
+            audio.silence();
+        }
+
+        render(
+            &game_state,
+            &camera,
+            alpha,
+            &assets,
+            &effects,
+            &stats,
+            &mut debug_renderer,
+        );
+        scenes.draw(&game_state, &stats);
 
         next_frame().await
     }
 }
+
+/// Writes the header for a recording of `game_state` from here on.
+fn start_recording(path: &Path, cli_args: &CliArgs, game_state: &GameState) -> ReplayRecorder {
+    let header = ReplayHeader {
+        spawn: game_state.player.center,
+        level: cli_args.level.clone(),
+        seed: cli_args.seed,
+        tuning: game_state.player.tuning.clone(),
+    };
+    ReplayRecorder::create(path, &header).unwrap_or_else(|error| {
+        eprintln!("couldn't create {}: {}", path.display(), error);
+        exit(1);
+    })
+}
+
+/// Only keeps the old camera tuning if the file's broken, like the sim's
+/// tuning does.
+fn reload_camera_tuning(path: &Path, camera_tuning: &mut CameraTuning) {
+    match CameraTuning::load(path) {
+        Ok(loaded) => *camera_tuning = loaded,
+        Err(error) => eprintln!("{}: {}", path.display(), error),
+    }
+}
+
+/// Bindings from `--bindings`, or the default file. If the default file
+/// doesn't exist it's created with the default bindings.
+fn load_bindings(cli_args: &CliArgs) -> Bindings {
+    let path = cli_args
+        .bindings
+        .clone()
+        .unwrap_or_else(|| DEFAULT_BINDINGS_PATH.into());
+
+    if cli_args.bindings.is_none() && !path.exists() {
+        let bindings = Bindings::default();
+        if let Err(error) = bindings.save(&path) {
+            eprintln!("couldn't write {}: {}", path.display(), error);
+        }
+        return bindings;
+    }
+
+    Bindings::load(&path).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path.display(), error);
+        exit(1);
+    })
+}
+
+/// Replays start however the recording did, otherwise use the level or city
+/// from the command line (or the built in test setup if there isn't one).
+fn load_game_state(cli_args: &CliArgs, replay: Option<&Replay>) -> GameState {
+    if let Some(replay) = replay {
+        return replay.new_game_state().unwrap_or_else(|error| {
+            eprintln!("couldn't load the replay's level: {}", error);
+            exit(1);
+        });
+    }
+
+    match (&cli_args.level, cli_args.seed) {
+        (Some(path), _) => {
+            let level = Level::load(path).unwrap_or_else(|error| {
+                eprintln!("{}: {}", path.display(), error);
+                exit(1);
+            });
+            GameState::from_level(&level)
+        }
+        (None, Some(seed)) => GameState::from_level(&generate_city(seed)),
+        // FIXME: have player instantiated in gamestate, shouldn't be here.
+        (None, None) => GameState::new(Player::new(screen_width() / 2.0, 120.0)),
+    }
+}
@@ -1,43 +1,144 @@
-use crate::constants::{
-    CAMERA_SPEED, CAR_DEFAULT_DRAG, CRAZY_DASH_LENGTH, CRAZY_DASH_MAX_VELOCITY,
-    CRAZY_DASH_VELOCITY, GAS_VELOCITY, IS_DEBUG, PLAYER_MAX_REVERSE_VELOCITY, PLAYER_MAX_VELOCITY,
-    PLAYER_ROTATION_SPEED, REVERSE_VELOCITY,
-};
+use crate::assets::Assets;
+use crate::camera::Camera;
+use crate::constants::IS_DEBUG;
 use crate::debug::{DebugRenderer, render_grid};
-use crate::gamestate::GameState;
-use crate::math::convert_world_pos_to_camera_pos;
-use crate::player::ShiftMode;
+use crate::effects::{Effects, SCORE_POPUP_TIME};
 use macroquad::prelude::*;
-
-pub fn render(game_state: &GameState, camera: &Rect, debug_renderer: &mut DebugRenderer) {
+use taxi_sim::constants::MAX_HEALTH;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::level::{Region, RegionKind};
+use taxi_sim::math::{Point, Rect, polygon_bounds, polygon_collision};
+use taxi_sim::passenger::PassengerState;
+use taxi_sim::player::{Player, ShiftMode};
+use taxi_sim::roads::Route;
+use taxi_sim::stats::Stats;
+use taxi_sim::tilemap::{Tile, Tilemap};
+use taxi_sim::wall::Wall;
+
+/// The route arrow points at the first turn at least this far from the taxi,
+/// so it doesn't swing around as the taxi drives over the turn.
+const ROUTE_ARROW_MIN_DISTANCE: f32 = 60.0;
+/// World units covered by one repeat of the ground texture...
+const GROUND_TEXTURE_SIZE: f32 = 256.0;
+/// ...and the wall texture.
+const WALL_TEXTURE_SIZE: f32 = 128.0;
+const ROAD_COLOR: Color = Color::new(0.16, 0.16, 0.18, 1.0);
+const SIDEWALK_COLOR: Color = Color::new(0.42, 0.42, 0.4, 1.0);
+/// Score pop-ups float up this many pixels over their lifetime.
+const SCORE_POPUP_RISE: f32 = 60.0;
+
+/// `alpha` is how far between the last two simulation ticks we are, see
+/// `FixedTimestep::alpha`. Draws with `assets` unless the debug wireframe
+/// view is on.
+pub fn render(
+    game_state: &GameState,
+    camera: &Camera,
+    alpha: f32,
+    assets: &Assets,
+    effects: &Effects,
+    stats: &Stats,
+    debug_renderer: &mut DebugRenderer,
+) {
+    let textures = (!debug_renderer.debug_state.wireframe).then_some(assets);
     clear_background(BLACK);
 
     // Kept separate from debug info since it should be under everything else.
-    if IS_DEBUG {
-        render_grid(camera);
+    match textures {
+        Some(assets) => render_ground(&assets.ground, camera),
+        None if IS_DEBUG => render_grid(camera),
+        None => (),
     }
 
-    render_player(&game_state.player, camera);
-    render_wall(&game_state.wall, camera);
-    render_ui(game_state);
+    render_tiles(game_state, camera);
+    render_roads(game_state, camera);
+    render_passenger_zones(game_state, camera);
+    let route = game_state.route_to_destination();
+    if let Some(route) = &route {
+        render_route(game_state, route, camera);
+    }
+    if IS_DEBUG && textures.is_none() {
+        render_lanes(game_state, camera);
+        render_loaded_chunks(game_state, camera);
+    }
+    for traffic_car in game_state.traffic.iter() {
+        render_player(&traffic_car.car, camera, alpha, SKYBLUE, None, textures);
+    }
+    render_player(
+        &game_state.player,
+        camera,
+        alpha,
+        YELLOW,
+        Some(effects),
+        textures,
+    );
+    // Only bother drawing walls that are on screen.
+    for wall in game_state.walls_in(&camera.view_rect()) {
+        match textures {
+            Some(assets) => render_textured_wall(wall, &assets.wall, camera),
+            None => render_wall(wall, camera),
+        }
+    }
+    render_score_popups(effects, camera);
+    render_ui(game_state, effects);
+    if let Some(route) = &route {
+        render_route_arrow(game_state, route, alpha);
+    }
 
     if IS_DEBUG {
-        render_debug_info(game_state, camera, debug_renderer);
+        render_debug_info(game_state, camera, effects, stats, debug_renderer);
     }
 }
 
-fn render_player(player: &crate::player::Player, camera: &Rect) {
-    for i in 0..player.points.len() {
-        let current = player.points[i];
-        let next = player.points[(i + 1) % player.points.len()]; // Wrap around to first point
-
-        let curr_vertex_camera_pos = convert_world_pos_to_camera_pos(&current, camera);
-        let next_vertex_camera_pos = convert_world_pos_to_camera_pos(&next, camera);
-
-        let mut outline_color = YELLOW;
-        if player.is_crazy_dashing {
+/// Also used for traffic, which is the same kind of car in another `color`
+/// (and without any `effects`). Without `textures` it's drawn as an outline.
+fn render_player(
+    player: &Player,
+    camera: &Camera,
+    alpha: f32,
+    color: Color,
+    effects: Option<&Effects>,
+    textures: Option<&Assets>,
+) {
+    let points = player.interpolated_points(alpha);
+    let center = player.interpolated_center(alpha);
+    let mut outline_color = damaged_color(player, color);
+    if let Some(effects) = effects {
+        if effects.taxi_dashing {
             outline_color = BLUE;
         }
+        outline_color = lerp_color(outline_color, WHITE, effects.hit_flash);
+    }
+
+    if let Some(assets) = textures {
+        // Points go around from the back left corner to the front left one,
+        // so that edge is which way the car's facing.
+        let facing = points[0] - points[7];
+        let rotation = facing.x.atan2(-facing.y);
+        let shape = &player.tuning.player_shape;
+        let width = camera.scale(shape.half_width * 2.0);
+        let height = camera.scale((shape.half_height + shape.inner_space_h) * 2.0);
+        let screen_center = camera.world_to_screen(&center);
+
+        draw_texture_ex(
+            &assets.car,
+            screen_center.x - width / 2.0,
+            screen_center.y - height / 2.0,
+            outline_color,
+            DrawTextureParams {
+                dest_size: Some(vec2(width, height)),
+                rotation,
+                ..Default::default()
+            },
+        );
+        return;
+    }
+
+    for i in 0..points.len() {
+        let current = points[i];
+        let next = points[(i + 1) % points.len()]; // Wrap around to first point
+
+        let curr_vertex_camera_pos = camera.world_to_screen(&current);
+        let next_vertex_camera_pos = camera.world_to_screen(&next);
 
         draw_line(
             curr_vertex_camera_pos.x,
@@ -50,7 +151,7 @@ fn render_player(player: &crate::player::Player, camera: &Rect) {
         draw_circle(curr_vertex_camera_pos.x, curr_vertex_camera_pos.y, 3.0, RED);
     }
 
-    let player_center_camera_pos = convert_world_pos_to_camera_pos(&player.center, camera);
+    let player_center_camera_pos = camera.world_to_screen(&center);
     draw_circle(
         player_center_camera_pos.x,
         player_center_camera_pos.y,
@@ -58,8 +159,8 @@ fn render_player(player: &crate::player::Player, camera: &Rect) {
         RED,
     );
 
-    let player_normal_world_pos = player.get_player_normal_vector_for_debug(50.0);
-    let player_normal = convert_world_pos_to_camera_pos(&player_normal_world_pos, camera);
+    let player_normal_world_pos = center + player.forward_normal * 50.0;
+    let player_normal = camera.world_to_screen(&player_normal_world_pos);
 
     draw_line(
         player_center_camera_pos.x,
@@ -72,13 +173,161 @@ fn render_player(player: &crate::player::Player, camera: &Rect) {
     draw_circle(player_normal.x, player_normal.y, 5.0, GREEN);
 }
 
-fn render_wall(wall: &crate::wall::Wall, camera: &Rect) {
+/// Repeats `texture` across the whole view, fixed to the world so it scrolls
+/// with everything else.
+fn render_ground(texture: &Texture2D, camera: &Camera) {
+    let view = camera.view_rect();
+    let corners = [
+        Point::new(view.x, view.y),
+        Point::new(view.x + view.w, view.y),
+        Point::new(view.x + view.w, view.y + view.h),
+        Point::new(view.x, view.y + view.h),
+    ];
+    draw_mesh(&textured_mesh(
+        &corners,
+        texture,
+        GROUND_TEXTURE_SIZE,
+        camera,
+    ));
+}
+
+/// Road and sidewalk tiles. Buildings are walls, so they're drawn with the
+/// rest of the walls.
+fn render_tiles(game_state: &GameState, camera: &Camera) {
+    for (tile_x, tile_y, tile) in game_state.tilemap.tiles_in(&camera.view_rect()) {
+        let color = match tile {
+            Tile::Road => ROAD_COLOR,
+            Tile::Sidewalk => SIDEWALK_COLOR,
+            Tile::Empty | Tile::Building => continue,
+        };
+        let bounds = Tilemap::tile_bounds(tile_x, tile_y);
+        let top_left = camera.world_to_screen(&Point::new(bounds.x, bounds.y));
+        // Rounded out a pixel so there aren't gaps between tiles.
+        let size = camera.scale(bounds.w) + 1.0;
+        draw_rectangle(top_left.x, top_left.y, size, size, color);
+    }
+}
+
+fn render_roads(game_state: &GameState, camera: &Camera) {
+    let view = camera.view_rect();
+    let nodes = &game_state.roads.nodes;
+
+    for road in game_state.roads.edges.iter() {
+        let (from, to) = (nodes[road.from], nodes[road.to]);
+        let half_width = road.width() / 2.0;
+        let bounds = polygon_bounds(&[from, to]);
+        let bounds = Rect::new(
+            bounds.x - half_width,
+            bounds.y - half_width,
+            bounds.w + road.width(),
+            bounds.h + road.width(),
+        );
+        if !bounds.overlaps(&view) {
+            continue;
+        }
+
+        let from = camera.world_to_screen(&from);
+        let to = camera.world_to_screen(&to);
+        let width = camera.scale(road.width());
+        draw_line(from.x, from.y, to.x, to.y, width, ROAD_COLOR);
+        // Round off the ends so corners join up.
+        draw_circle(from.x, from.y, width / 2.0, ROAD_COLOR);
+        draw_circle(to.x, to.y, width / 2.0, ROAD_COLOR);
+    }
+}
+
+/// Line along the roads to wherever the taxi's going, same color as the zone
+/// it ends at.
+fn render_route(game_state: &GameState, route: &Route, camera: &Camera) {
+    let color = route_color(game_state);
+    for pair in route.points.windows(2) {
+        let from = camera.world_to_screen(&pair[0]);
+        let to = camera.world_to_screen(&pair[1]);
+        draw_line(from.x, from.y, to.x, to.y, 4.0, color);
+    }
+}
+
+/// Arrow at the top of the screen pointing at the next turn.
+fn render_route_arrow(game_state: &GameState, route: &Route, alpha: f32) {
+    let center = game_state.player.interpolated_center(alpha);
+    let Some(next_turn) = route.next_turn(&center, ROUTE_ARROW_MIN_DISTANCE) else {
+        return;
+    };
+
+    let direction = (next_turn - center).normalized();
+    let side = Point::new(-direction.y, direction.x);
+    let arrow_center = Point::new(screen_width() / 2.0, 100.0);
+    let tip = arrow_center + direction * 30.0;
+    let left = arrow_center - direction * 20.0 - side * 20.0;
+    let right = arrow_center - direction * 20.0 + side * 20.0;
+
+    draw_triangle(
+        vec2(tip.x, tip.y),
+        vec2(left.x, left.y),
+        vec2(right.x, right.y),
+        route_color(game_state),
+    );
+}
+
+fn route_color(game_state: &GameState) -> Color {
+    if game_state.riding_passenger().is_some() {
+        YELLOW
+    } else {
+        GREEN
+    }
+}
+
+/// Goes towards a burnt out red the more damaged the car is, and gray once
+/// it's wrecked.
+fn damaged_color(player: &Player, color: Color) -> Color {
+    if player.is_wrecked() {
+        return DARKGRAY;
+    }
+
+    let damage = 1.0 - player.health / MAX_HEALTH;
+    let burnt = Color::new(0.5, 0.12, 0.05, 1.0);
+    lerp_color(color, burnt, damage)
+}
+
+fn lerp_color(from: Color, to: Color, amount: f32) -> Color {
+    Color::new(
+        from.r + (to.r - from.r) * amount,
+        from.g + (to.g - from.g) * amount,
+        from.b + (to.b - from.b) * amount,
+        from.a + (to.a - from.a) * amount,
+    )
+}
+
+/// Where traffic drives, with a dot on each waypoint.
+fn render_lanes(game_state: &GameState, camera: &Camera) {
+    for lane in game_state.lanes.iter() {
+        for i in 0..lane.points.len() {
+            let current = camera.world_to_screen(&lane.points[i]);
+            let next = camera.world_to_screen(&lane.points[(i + 1) % lane.points.len()]);
+
+            draw_line(current.x, current.y, next.x, next.y, 1.0, DARKGRAY);
+            draw_circle(current.x, current.y, 4.0, DARKGRAY);
+        }
+    }
+}
+
+/// Outline of each chunk whose buildings are loaded as walls.
+fn render_loaded_chunks(game_state: &GameState, camera: &Camera) {
+    for coord in game_state.loaded_chunks() {
+        let bounds = coord.bounds();
+        let top_left = camera.world_to_screen(&Point::new(bounds.x, bounds.y));
+        let size = camera.scale(bounds.w);
+        draw_rectangle_lines(top_left.x, top_left.y, size, size, 2.0, DARKGREEN);
+    }
+}
+
+fn render_wall(wall: &Wall, camera: &Camera) {
     for i in 0..wall.points.len() {
         let current = wall.points[i];
         let next = wall.points[(i + 1) % wall.points.len()]; // Wrap around to first point
 
-        let curr_vertex_camera_pos = convert_world_pos_to_camera_pos(&current, camera);
-        let next_vertex_camera_pos = convert_world_pos_to_camera_pos(&next, camera);
+        let curr_vertex_camera_pos = camera.world_to_screen(&current);
+        let next_vertex_camera_pos = camera.world_to_screen(&next);
 
         draw_line(
             curr_vertex_camera_pos.x,
@@ -89,10 +338,15 @@ fn render_wall(wall: &crate::wall::Wall, camera: &Rect) {
             WHITE,
         );
 
-        draw_circle(curr_vertex_camera_pos.x, curr_vertex_camera_pos.y, 4.0, PURPLE);
+        draw_circle(
+            curr_vertex_camera_pos.x,
+            curr_vertex_camera_pos.y,
+            4.0,
+            PURPLE,
+        );
     }
 
-    let wall_center_camera_pos = convert_world_pos_to_camera_pos(&wall.center, camera);
+    let wall_center_camera_pos = camera.world_to_screen(&wall.center);
     draw_circle(
         wall_center_camera_pos.x,
         wall_center_camera_pos.y,
@@ -101,89 +355,305 @@ fn render_wall(wall: &crate::wall::Wall, camera: &Rect) {
     );
 }
 
-fn render_ui(game_state: &GameState) {
-    draw_text("Hello, taxi!", 20.0, 20.0, 30.0, WHITE);
+/// Brick fill, with a dark edge so walls stand out from each other.
+fn render_textured_wall(wall: &Wall, texture: &Texture2D, camera: &Camera) {
+    for part in wall.convex_parts.iter() {
+        draw_mesh(&textured_mesh(part, texture, WALL_TEXTURE_SIZE, camera));
+    }
+
+    for i in 0..wall.points.len() {
+        let current = camera.world_to_screen(&wall.points[i]);
+        let next = camera.world_to_screen(&wall.points[(i + 1) % wall.points.len()]);
+        draw_line(current.x, current.y, next.x, next.y, 2.0, DARKBROWN);
+    }
+}
+
+/// Fills a convex polygon with `texture`, repeating every `texture_size`
+/// world units. The texture needs to be set to repeat (see `assets.rs`).
+fn textured_mesh(
+    polygon: &[Point],
+    texture: &Texture2D,
+    texture_size: f32,
+    camera: &Camera,
+) -> Mesh {
+    let vertices = polygon
+        .iter()
+        .map(|point| {
+            let screen = camera.world_to_screen(point);
+            Vertex::new(
+                screen.x,
+                screen.y,
+                0.0,
+                point.x / texture_size,
+                point.y / texture_size,
+                WHITE,
+            )
+        })
+        .collect();
+    // Fan out from the first point, fine since it's convex.
+    let indices = (1..polygon.len().saturating_sub(1) as u16)
+        .flat_map(|i| [0, i, i + 1])
+        .collect();
+
+    Mesh {
+        vertices,
+        indices,
+        texture: Some(texture.clone()),
+    }
+}
+
+fn render_ui(game_state: &GameState, effects: &Effects) {
+    render_shift_clock(game_state);
     draw_text(
-        "Arrow keys to move, click to teleport",
+        "Arrow keys to move, shift to brake, N/P for neutral/park, click to teleport (right click stops), Esc to pause",
         20.0,
         50.0,
         20.0,
         WHITE,
     );
 
+    render_health_bar(game_state);
+    render_boost_meter(game_state, effects);
+    render_score(game_state);
     render_gear_indicator(game_state);
+    render_fare_hud(game_state);
 }
 
-fn render_gear_indicator(game_state: &GameState) {
-    let screen_width = screen_width();
-    let screen_height = screen_height();
+/// Top left, red for the last minute.
+fn render_shift_clock(game_state: &GameState) {
+    let time_left = game_state.shift_time_left().ceil() as u32;
+    let text = format!("Shift: {}:{:02} left", time_left / 60, time_left % 60);
+    let color = if time_left <= 60 { RED } else { WHITE };
+    draw_text(&text, 20.0, 25.0, 30.0, color);
+}
 
-    let indicator_size = 60.0;
-    let margin = 20.0;
-    let base_x = screen_width - indicator_size - margin;
-    let base_y = screen_height - (indicator_size * 2.0) - margin;
+/// Under the instructions in the top left.
+fn render_health_bar(game_state: &GameState) {
+    let (x, y, width, height) = (20.0, 65.0, 200.0, 14.0);
+    let health = game_state.player.health / MAX_HEALTH;
+
+    draw_rectangle(x, y, width, height, Color::new(0.2, 0.2, 0.2, 1.0));
+    draw_rectangle(
+        x,
+        y,
+        width * health,
+        height,
+        damaged_color(&game_state.player, GREEN),
+    );
+    draw_rectangle_lines(x, y, width, height, 2.0, WHITE);
 
-    let shift_mode = game_state.player.shift_mode;
+    if game_state.player.is_wrecked() {
+        draw_text("WRECKED", x + width + 10.0, y + 13.0, 22.0, RED);
+    }
+}
 
-    let drive_color = match shift_mode {
-        ShiftMode::DRIVE => Color::new(0.0, 0.8, 0.0, 1.0), // Bright green when active
-        ShiftMode::REVERSE => Color::new(0.0, 0.4, 0.0, 1.0), // Dark green when inactive
+/// Under the health bar, marked where there's enough for a dash.
+fn render_boost_meter(game_state: &GameState, effects: &Effects) {
+    let player = &game_state.player;
+    let (x, y, width, height) = (20.0, 85.0, 200.0, 10.0);
+    let color = if effects.taxi_dashing {
+        BLUE
+    } else if player.boost >= player.tuning.crazy_dash_boost_cost {
+        SKYBLUE
+    } else {
+        GRAY
     };
 
-    let reverse_color = match shift_mode {
-        ShiftMode::REVERSE => Color::new(1.0, 0.0, 0.0, 1.0), // Bright red when active
-        ShiftMode::DRIVE => Color::new(0.4, 0.0, 0.0, 1.0),   // Dark red when inactive
-    };
+    draw_rectangle(x, y, width, height, Color::new(0.2, 0.2, 0.2, 1.0));
+    draw_rectangle(x, y, width * player.boost, height, color);
+    // One tick per dash's worth.
+    let cost = player.tuning.crazy_dash_boost_cost;
+    if cost > 0.0 {
+        let mut mark = cost;
+        while mark < 1.0 {
+            let mark_x = x + width * mark;
+            draw_line(mark_x, y, mark_x, y + height, 1.0, BLACK);
+            mark += cost;
+        }
+    }
+    draw_rectangle_lines(x, y, width, height, 2.0, WHITE);
 
-    draw_rectangle(base_x, base_y, indicator_size, indicator_size, drive_color);
-    draw_rectangle_lines(base_x, base_y, indicator_size, indicator_size, 2.0, WHITE);
-    draw_text("D", base_x + 18.0, base_y + 35.0, 40.0, WHITE);
+    if player.crazy_dash_chain > 1 {
+        let text = format!("DASH x{}", player.crazy_dash_chain);
+        draw_text(&text, x + width + 10.0, y + 10.0, 22.0, SKYBLUE);
+    }
+}
 
-    let reverse_y = base_y + indicator_size + 5.0;
+/// Under the boost meter, with the combo if there is one.
+fn render_score(game_state: &GameState) {
+    let scoring = &game_state.scoring;
+    let (x, y) = (20.0, 125.0);
+    let text = format!("SCORE {}", scoring.total);
+    draw_text(&text, x, y, 30.0, WHITE);
+
+    if scoring.combo > 1.0 {
+        let text_width = measure_text(&text, None, 30, 1.0).width;
+        let combo = format!("x{:.1}", scoring.combo);
+        draw_text(&combo, x + text_width + 10.0, y, 30.0, ORANGE);
+    }
+}
+
+/// Trick names and points where they happened, floating up and fading out.
+fn render_score_popups(effects: &Effects, camera: &Camera) {
+    let font_size = 24.0;
+    for popup in effects.score_popups.iter() {
+        let progress = popup.age / SCORE_POPUP_TIME;
+        let position = camera.world_to_screen(&popup.position);
+        let text_width = measure_text(&popup.text, None, font_size as u16, 1.0).width;
+        draw_text(
+            &popup.text,
+            position.x - text_width / 2.0,
+            position.y - 40.0 - SCORE_POPUP_RISE * progress,
+            font_size,
+            Color::new(1.0, 0.85, 0.2, 1.0 - progress),
+        );
+    }
+}
+
+/// Outlines where passengers are waiting, and where the current one wants
+/// to go.
+fn render_passenger_zones(game_state: &GameState, camera: &Camera) {
+    for passenger in game_state.passengers.iter() {
+        let (region_name, color) = match passenger.state {
+            PassengerState::Waiting => (&passenger.pickup, GREEN),
+            PassengerState::Riding { .. } => (&passenger.destination, YELLOW),
+        };
+        let Some(region) = game_state.region(region_name) else {
+            continue;
+        };
+        render_zone(region, color, camera);
+    }
+
+    // Garages are always open.
+    for region in game_state.regions.iter() {
+        if region.kind == RegionKind::Garage {
+            render_zone(region, SKYBLUE, camera);
+        }
+    }
+}
+
+fn render_zone(region: &Region, color: Color, camera: &Camera) {
+    let top_left = camera.world_to_screen(&Point::new(region.area.x, region.area.y));
+    let width = camera.scale(region.area.w);
+    let height = camera.scale(region.area.h);
     draw_rectangle(
-        base_x,
-        reverse_y,
-        indicator_size,
-        indicator_size,
-        reverse_color,
+        top_left.x,
+        top_left.y,
+        width,
+        height,
+        Color::new(color.r, color.g, color.b, 0.15),
     );
-    draw_rectangle_lines(
-        base_x,
-        reverse_y,
-        indicator_size,
-        indicator_size,
-        2.0,
-        WHITE,
+    draw_rectangle_lines(top_left.x, top_left.y, width, height, 3.0, color);
+    draw_text(
+        &region.name,
+        top_left.x + 5.0,
+        top_left.y + 20.0,
+        20.0,
+        color,
     );
-    draw_text("R", base_x + 18.0, reverse_y + 35.0, 40.0, WHITE);
+}
+
+/// Money and what the current passenger wants, left of the gear indicator.
+fn render_fare_hud(game_state: &GameState) {
+    // Gear indicator is 60 wide with a 20 margin.
+    let right_x = screen_width() - 60.0 - 20.0 * 2.0;
+    let base_y = screen_height() - 110.0;
+
+    let draw_right_aligned = |text: &str, y: f32, font_size: f32, color: Color| {
+        let text_width = measure_text(text, None, font_size as u16, 1.0).width;
+        draw_text(text, right_x - text_width, y, font_size, color);
+    };
+
+    draw_right_aligned(&format!("$ {:.2}", game_state.money), base_y, 40.0, GREEN);
+
+    let status = match game_state.riding_passenger() {
+        Some(passenger) => format!(
+            "Drop off at {} ({:.1}s)",
+            passenger.destination,
+            passenger.ride_time(game_state.time).unwrap_or_default()
+        ),
+        None if game_state.passengers.is_empty() => "No passengers waiting".to_owned(),
+        None => "Stop in a green zone to pick up".to_owned(),
+    };
+    draw_right_aligned(&status, base_y + 30.0, 22.0, WHITE);
+
+    // Show what the last ride paid for a few seconds.
+    if let Some(fare) = game_state.fares.last()
+        && game_state.time - fare.paid_at < 3.0
+    {
+        draw_right_aligned(
+            &format!("+ $ {:.2} ({:.1}s)", fare.amount, fare.ride_time),
+            base_y + 60.0,
+            26.0,
+            YELLOW,
+        );
+    }
+}
+
+/// P/R/N/D stack, like the shifter in a real car.
+fn render_gear_indicator(game_state: &GameState) {
+    let screen_width = screen_width();
+    let screen_height = screen_height();
+
+    let indicator_width = 60.0;
+    let indicator_height = 30.0;
+    let spacing = 5.0;
+    let margin = 20.0;
+    let base_x = screen_width - indicator_width - margin;
+
+    // (label, gear, bright color when active)
+    let gears = [
+        ("P", ShiftMode::PARK, Color::new(0.2, 0.4, 1.0, 1.0)),
+        ("R", ShiftMode::REVERSE, Color::new(1.0, 0.0, 0.0, 1.0)),
+        ("N", ShiftMode::NEUTRAL, Color::new(1.0, 0.7, 0.0, 1.0)),
+        ("D", ShiftMode::DRIVE, Color::new(0.0, 0.8, 0.0, 1.0)),
+    ];
+    let base_y =
+        screen_height - margin - (indicator_height + spacing) * gears.len() as f32 + spacing;
+
+    for (i, (label, gear, active_color)) in gears.iter().enumerate() {
+        let y = base_y + (indicator_height + spacing) * i as f32;
+        let color = if game_state.player.shift_mode == *gear {
+            *active_color
+        } else {
+            // Dimmed when inactive
+            Color::new(
+                active_color.r * 0.4,
+                active_color.g * 0.4,
+                active_color.b * 0.4,
+                1.0,
+            )
+        };
+
+        draw_rectangle(base_x, y, indicator_width, indicator_height, color);
+        draw_rectangle_lines(base_x, y, indicator_width, indicator_height, 2.0, WHITE);
+        draw_text(label, base_x + 22.0, y + 23.0, 30.0, WHITE);
+    }
 }
 
 // TODO: move this into debug module, and then call it from main.
 // Would that makes sense? Just wnat to be able to access delta time, etc
-fn render_debug_info(game_state: &GameState, camera: &Rect, debug_renderer: &mut DebugRenderer) {
+fn render_debug_info(
+    game_state: &GameState,
+    camera: &Camera,
+    effects: &Effects,
+    stats: &Stats,
+    debug_renderer: &mut DebugRenderer,
+) {
     debug_renderer.reset();
 
-    // Add constants to top-right corner
-    debug_renderer.add_constant(&format!("GAS_VELOCITY: {}", GAS_VELOCITY));
-    debug_renderer.add_constant(&format!("CRAZY_DASH_VELOCITY: {}", CRAZY_DASH_VELOCITY));
-    debug_renderer.add_constant(&format!("REVERSE_VELOCITY: {}", REVERSE_VELOCITY));
-    debug_renderer.add_constant(&format!("DASH_LENGTH: {}", CRAZY_DASH_LENGTH));
-    debug_renderer.add_constant(&format!("DASH_MAX_VELOCITY: {}", CRAZY_DASH_MAX_VELOCITY));
-    debug_renderer.add_constant(&format!("MAX_VELOCITY: {}", PLAYER_MAX_VELOCITY));
-    debug_renderer.add_constant(&format!(
-        "MAX_REV_VELOCITY: {}",
-        PLAYER_MAX_REVERSE_VELOCITY
-    ));
-    debug_renderer.add_constant(&format!("ROTATION_SPEED: {}", PLAYER_ROTATION_SPEED));
-    debug_renderer.add_constant(&format!("CAR_DEFAULT_DRAG: {}", CAR_DEFAULT_DRAG));
-    debug_renderer.add_constant(&format!("CAMERA_SPEED: {}", CAMERA_SPEED));
+    // Add tuning to top-right corner, same as it'd look in the tuning file.
+    for line in game_state.player.tuning.to_toml().lines() {
+        debug_renderer.add_constant(line);
+    }
 
     debug_renderer.add_text(&format!(
         "Player Center: ({:.1}, {:.1})",
         game_state.player.center.x, game_state.player.center.y
     ));
 
-    //debug_renderer.add_text(&format!("Camera: ({:.1}, {:.1})", camera.x, camera.y));
+    //debug_renderer.add_text(&format!("Camera: ({:.1}, {:.1})", camera.center.x, camera.center.y));
+    debug_renderer.add_text(&format!("camera zoom: {:.2}", camera.zoom));
 
     //for (i, point) in game_state.player.points.iter().enumerate() {
     //debug_renderer.add_text(&format!("Point {}: ({:.1}, {:.1})", i, point.x, point.y));
@@ -194,6 +664,20 @@ fn render_debug_info(game_state: &GameState, camera: &Rect, debug_renderer: &mut
         game_state.player.velocity.x, game_state.player.velocity.y
     ));
 
+    debug_renderer.add_text(&format!(
+        "forward/lateral speed: ({:.2}, {:.2})",
+        game_state.player.forward_speed(),
+        game_state.player.lateral_speed()
+    ));
+
+    debug_renderer.add_text(&format!("health: {:.1}", game_state.player.health));
+
+    debug_renderer.add_text(&format!(
+        "drifting: {}, drift angle: {:.1} deg",
+        game_state.player.drifting,
+        game_state.player.drift_angle().to_degrees()
+    ));
+
     debug_renderer.add_text(&format!(
         "ticks since switching to drive: {:.2}",
         game_state.player.ticks_since_switching_into_drive
@@ -219,12 +703,59 @@ fn render_debug_info(game_state: &GameState, camera: &Rect, debug_renderer: &mut
         game_state.player.is_crazy_dashing
     ));
 
+    debug_renderer.add_text(&format!(
+        "boost: {:.2}, dash chain: {} (strength {:.2})",
+        game_state.player.boost,
+        game_state.player.crazy_dash_chain,
+        game_state.player.crazy_dash_strength
+    ));
+
+    debug_renderer.add_text(&format!(
+        "score: {}, combo: x{:.2}",
+        game_state.scoring.total, game_state.scoring.combo
+    ));
+
     debug_renderer.add_text(&format!("drag: {:.2}", game_state.player.drag));
 
-    debug_renderer.add_text(&format!("time: {:.2}", get_time()));
+    debug_renderer.add_text(&format!(
+        "gear: {:?}, brake held for: {:.2}",
+        game_state.player.shift_mode, game_state.player.brake_held_time
+    ));
+
+    let wall_overlap = game_state
+        .walls_in(&game_state.player.bounds())
+        .flat_map(|wall| wall.convex_parts.iter())
+        .filter_map(|part| polygon_collision(&game_state.player.points, part))
+        .map(|collision| collision.overlap)
+        .fold(0.0, f32::max);
+    debug_renderer.add_text(&format!("wall overlap: {:.2}", wall_overlap));
+
+    debug_renderer.add_text(&format!(
+        "walls on screen: {} / {}",
+        game_state.walls_in(&camera.view_rect()).count(),
+        game_state.walls().len()
+    ));
+
+    debug_renderer.add_text(&format!(
+        "chunks loaded: {} / {}",
+        game_state.loaded_chunks().count(),
+        game_state.tilemap.chunk_count()
+    ));
+
+    debug_renderer.add_text(&format!("time: {:.2}", game_state.time));
+
+    debug_renderer.add_text(&format!(
+        "dashes: {} ({} perfect, best chain {}), gear changes: {}",
+        stats.dashes, stats.perfect_dashes, stats.longest_dash_chain, stats.gear_changes
+    ));
+
+    debug_renderer.add_text(&format!(
+        "hits: {} (hardest {:.0}), wrecks: {}, rides: {}/{}",
+        stats.collisions, stats.hardest_hit, stats.wrecks, stats.drop_offs, stats.pickups
+    ));
 
     // Crazy dash visual indicator - flashing blue square
-    if game_state.player.is_crazy_dashing && debug_renderer.debug_state.show_crazy_dash_indicator {
+    if effects.taxi_dashing && debug_renderer.debug_state.show_crazy_dash_indicator {
         //let flash_intensity = ((get_time() * 10.0).sin() * 0.5 + 0.5) as f32; // Flash between 0 and 1
         let blue_color = Color::new(0.0, 0.5, 1.0, 1.0);
 
@@ -0,0 +1,181 @@
+//! Which screen the game's on: the title menu, driving, paused, or the
+//! results once the shift's over. Only `Playing` runs the simulation, so the
+//! sim clock (and every timer on it, like dashes) stops everywhere else.
+//!
+//! The menus are drawn over the top of the game, which is still rendered
+//! underneath (just not moving).
+
+use crate::input::MenuAction;
+use macroquad::prelude::*;
+use taxi_sim::gamestate::GameState;
+use taxi_sim::stats::Stats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scene {
+    Title,
+    Playing,
+    Paused,
+    Results,
+}
+
+/// What picking something in a menu does; `main` takes care of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItem {
+    Start,
+    Resume,
+    /// A fresh shift, from the start.
+    Restart,
+    EndShift,
+    Quit,
+}
+
+impl MenuItem {
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Start => "Start shift",
+            MenuItem::Resume => "Resume",
+            MenuItem::Restart => "Restart shift",
+            MenuItem::EndShift => "End shift",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+impl Scene {
+    pub fn menu(self) -> &'static [MenuItem] {
+        match self {
+            Scene::Title => &[MenuItem::Start, MenuItem::Quit],
+            Scene::Playing => &[],
+            Scene::Paused => &[
+                MenuItem::Resume,
+                MenuItem::Restart,
+                MenuItem::EndShift,
+                MenuItem::Quit,
+            ],
+            Scene::Results => &[MenuItem::Restart, MenuItem::Quit],
+        }
+    }
+}
+
+/// The current scene, and what's selected in its menu.
+pub struct Scenes {
+    pub current: Scene,
+    selected: usize,
+}
+
+impl Scenes {
+    pub fn new(scene: Scene) -> Self {
+        Self {
+            current: scene,
+            selected: 0,
+        }
+    }
+
+    pub fn switch_to(&mut self, scene: Scene) {
+        self.current = scene;
+        self.selected = 0;
+    }
+
+    /// Moves around the menu, and pauses/resumes. Returns whatever was
+    /// picked, if anything.
+    pub fn handle_menu_action(&mut self, menu_action: MenuAction) -> Option<MenuItem> {
+        let menu = self.current.menu();
+        match (menu_action, self.current) {
+            (MenuAction::Pause, Scene::Playing) => self.switch_to(Scene::Paused),
+            (MenuAction::Pause, Scene::Paused) => return Some(MenuItem::Resume),
+            (MenuAction::Up, _) if !menu.is_empty() => {
+                self.selected = (self.selected + menu.len() - 1) % menu.len();
+            }
+            (MenuAction::Down, _) if !menu.is_empty() => {
+                self.selected = (self.selected + 1) % menu.len();
+            }
+            (MenuAction::Confirm, _) => return menu.get(self.selected).copied(),
+            _ => (),
+        }
+        None
+    }
+
+    /// Goes over the top of the rendered game. Draws nothing while playing.
+    pub fn draw(&self, game_state: &GameState, stats: &Stats) {
+        let center_x = screen_width() / 2.0;
+        let mut y = screen_height() / 4.0;
+
+        match self.current {
+            Scene::Playing => return,
+            Scene::Title => {
+                dim_screen(0.85);
+                draw_centered("TAXI", center_x, y, 100.0, YELLOW);
+                y += 50.0;
+                draw_centered(
+                    "Pick people up, drop them off, don't wreck the car",
+                    center_x,
+                    y,
+                    24.0,
+                    GRAY,
+                );
+            }
+            Scene::Paused => {
+                dim_screen(0.6);
+                draw_centered("PAUSED", center_x, y, 60.0, WHITE);
+            }
+            Scene::Results => {
+                dim_screen(0.85);
+                draw_centered("SHIFT OVER", center_x, y, 60.0, YELLOW);
+                for line in results(game_state, stats) {
+                    y += 32.0;
+                    draw_centered(&line, center_x, y, 28.0, WHITE);
+                }
+            }
+        }
+
+        y += 80.0;
+        for (index, item) in self.current.menu().iter().enumerate() {
+            let (text, color) = if index == self.selected {
+                (format!("> {} <", item.label()), YELLOW)
+            } else {
+                (item.label().to_owned(), GRAY)
+            };
+            draw_centered(&text, center_x, y, 36.0, color);
+            y += 45.0;
+        }
+    }
+}
+
+/// Summary of the shift, a line each.
+fn results(game_state: &GameState, stats: &Stats) -> Vec<String> {
+    let fares = &game_state.fares;
+    let earned: f32 = fares.iter().map(|fare| fare.amount).sum();
+    let best_fare = fares.iter().map(|fare| fare.amount).fold(0.0, f32::max);
+
+    let mut lines = vec![
+        format!("Rides: {}", fares.len()),
+        format!("Fares: $ {:.2} (best $ {:.2})", earned, best_fare),
+        format!("Money after repairs and tows: $ {:.2}", game_state.money),
+        format!("Score: {}", game_state.scoring.total),
+    ];
+    if stats.tricks > 0 {
+        lines.push(format!(
+            "Tricks: {} (best combo x{:.1})",
+            stats.tricks, stats.best_combo
+        ));
+    }
+    if stats.wrecks > 0 {
+        lines.push(format!("Wrecks: {}", stats.wrecks));
+    }
+    lines
+}
+
+fn dim_screen(alpha: f32) {
+    draw_rectangle(
+        0.0,
+        0.0,
+        screen_width(),
+        screen_height(),
+        Color::new(0.0, 0.0, 0.0, alpha),
+    );
+}
+
+fn draw_centered(text: &str, center_x: f32, y: f32, font_size: f32, color: Color) {
+    let width = measure_text(text, None, font_size as u16, 1.0).width;
+    draw_text(text, center_x - width / 2.0, y, font_size, color);
+}
@@ -0,0 +1,116 @@
+//! Sound effects made up on the spot, so the game doesn't need any audio
+//! files. Everything comes out as a mono 16 bit WAV, ready for
+//! `load_sound_from_bytes`.
+
+use std::f32::consts::TAU;
+use taxi_sim::rng::Rng;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Pitches (Hz) of the engine loops, low revs to high. The engine sound
+/// fades between these as the taxi speeds up, since macroquad can't change
+/// the pitch of a playing sound.
+pub const ENGINE_PITCHES: [f32; 4] = [45.0, 70.0, 105.0, 150.0];
+
+/// One second of engine at `pitch`. Whole numbers of cycles, so it loops
+/// without clicking as long as `pitch` is a whole number.
+pub fn engine(pitch: f32) -> Vec<u8> {
+    let samples = generate(1.0, |t, _| {
+        let phase = (t * pitch).fract();
+        // Saw for the growl, plus a bit of the octave up.
+        let saw = phase * 2.0 - 1.0;
+        let octave = (t * pitch * 2.0 * TAU).sin();
+        (saw * 0.7 + octave * 0.3) * 0.5
+    });
+    wav(&samples)
+}
+
+/// Looping tire squeal.
+pub fn squeal() -> Vec<u8> {
+    let mut rng = Rng::new(1);
+    let samples = generate(1.0, |t, _| {
+        // Wobbles a bit, but always a whole number of wobbles per loop.
+        let wobble = (t * 6.0 * TAU).sin() * 0.003;
+        let tone = ((t + wobble) * 1900.0 * TAU).sin();
+        (tone * 0.8 + rng.range_f32(-0.2, 0.2)) * 0.4
+    });
+    wav(&samples)
+}
+
+/// Short blip sweeping from `from` to `to` Hz, for gear changes.
+pub fn blip(from: f32, to: f32) -> Vec<u8> {
+    let length = 0.09;
+    let mut phase = 0.0;
+    let samples = generate(length, |t, dt| {
+        let progress = t / length;
+        phase += (from + (to - from) * progress) * dt;
+        let square = if (phase * TAU).sin() > 0.0 { 1.0 } else { -1.0 };
+        square * (1.0 - progress) * 0.3
+    });
+    wav(&samples)
+}
+
+/// Rushing air for a crazy dash: noise that swells then fades, getting
+/// brighter as it goes.
+pub fn whoosh() -> Vec<u8> {
+    let length = 0.6;
+    let mut rng = Rng::new(2);
+    let mut filtered = 0.0;
+    let samples = generate(length, |t, _| {
+        let progress = t / length;
+        let envelope = (progress * std::f32::consts::PI).sin();
+        // Cheap low pass, opening up over time.
+        let brightness = 0.05 + 0.4 * progress;
+        filtered += (rng.range_f32(-1.0, 1.0) - filtered) * brightness;
+        filtered * envelope * 0.9
+    });
+    wav(&samples)
+}
+
+/// Bang for hitting things; louder hits just play it louder.
+pub fn crash() -> Vec<u8> {
+    let length = 0.45;
+    let mut rng = Rng::new(3);
+    let samples = generate(length, |t, _| {
+        let decay = (-t * 12.0).exp();
+        let thump = (t * 60.0 * TAU).sin() * (-t * 20.0).exp();
+        (rng.range_f32(-1.0, 1.0) * decay * 0.6 + thump * 0.6).clamp(-1.0, 1.0)
+    });
+    wav(&samples)
+}
+
+/// Calls `sample(time, delta_time)` for `length` seconds of samples.
+fn generate(length: f32, mut sample: impl FnMut(f32, f32) -> f32) -> Vec<f32> {
+    let count = (length * SAMPLE_RATE as f32) as usize;
+    let delta_time = 1.0 / SAMPLE_RATE as f32;
+    (0..count)
+        .map(|index| sample(index as f32 * delta_time, delta_time))
+        .collect()
+}
+
+fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_size = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_size as usize);
+
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // bytes per second
+    bytes.extend_from_slice(&2u16.to_le_bytes()); // bytes per sample
+    bytes.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    bytes
+}
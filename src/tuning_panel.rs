@@ -0,0 +1,221 @@
+use crate::camera::CameraTuning;
+use macroquad::hash;
+use macroquad::prelude::*;
+use macroquad::ui::{root_ui, widgets};
+use std::ops::Range;
+use std::path::PathBuf;
+use taxi_sim::tuning::Tuning;
+
+/// Where the export button writes to. Separate from the tuning file that's
+/// loaded on startup so exporting never clobbers the comments in it; copy
+/// it over once happy with the values.
+pub const TUNING_EXPORT_PATH: &str = "tuning.export.toml";
+
+/// Slider label, range and which value it edits.
+type Parameter<T> = (&'static str, Range<f32>, fn(&mut T) -> &mut f32);
+
+const PARAMETERS: &[Parameter<Tuning>] = &[
+    ("gas_velocity", 10.0..3000.0, |t| &mut t.gas_velocity),
+    ("reverse_velocity", 10.0..5000.0, |t| {
+        &mut t.reverse_velocity
+    }),
+    ("player_max_velocity", 10.0..2000.0, |t| {
+        &mut t.player_max_velocity
+    }),
+    ("player_max_reverse_velocity", -1000.0..-10.0, |t| {
+        &mut t.player_max_reverse_velocity
+    }),
+    ("player_rotation_speed", 0.5..50.0, |t| {
+        &mut t.player_rotation_speed
+    }),
+    ("crazy_dash_velocity", 10.0..10000.0, |t| {
+        &mut t.crazy_dash_velocity
+    }),
+    ("crazy_dash_max_velocity", 10.0..5000.0, |t| {
+        &mut t.crazy_dash_max_velocity
+    }),
+    ("crazy_dash_drag", 0.0..1000.0, |t| &mut t.crazy_dash_drag),
+    ("crazy_dash_drag_recovery", 0.0..500_000.0, |t| {
+        &mut t.crazy_dash_drag_recovery
+    }),
+    ("crazy_dash_chain_bonus", 0.0..1.0, |t| {
+        &mut t.crazy_dash_chain_bonus
+    }),
+    ("crazy_dash_boost_cost", 0.0..1.0, |t| {
+        &mut t.crazy_dash_boost_cost
+    }),
+    ("crazy_dash_boost_regen", 0.0..1.0, |t| {
+        &mut t.crazy_dash_boost_regen
+    }),
+    ("car_default_drag", 0.0..2000.0, |t| &mut t.car_default_drag),
+    ("brake_deceleration", 10.0..5000.0, |t| {
+        &mut t.brake_deceleration
+    }),
+    ("brake_initial_strength", 0.0..1.0, |t| {
+        &mut t.brake_initial_strength
+    }),
+    ("brake_ramp_time", 0.01..2.0, |t| &mut t.brake_ramp_time),
+    ("reverse_engage_max_speed", 0.0..500.0, |t| {
+        &mut t.reverse_engage_max_speed
+    }),
+    ("park_engage_max_speed", 0.0..100.0, |t| {
+        &mut t.park_engage_max_speed
+    }),
+    ("tire_grip", 10.0..5000.0, |t| &mut t.tire_grip),
+    ("tire_sliding_grip", 0.0..5000.0, |t| {
+        &mut t.tire_sliding_grip
+    }),
+    ("wall_restitution", 0.0..1.0, |t| &mut t.wall_restitution),
+    ("shape half_width", 5.0..100.0, |t| {
+        &mut t.player_shape.half_width
+    }),
+    ("shape half_height", 5.0..100.0, |t| {
+        &mut t.player_shape.half_height
+    }),
+    ("shape inner_space_w", 0.0..50.0, |t| {
+        &mut t.player_shape.inner_space_w
+    }),
+    ("shape inner_space_h", 0.0..50.0, |t| {
+        &mut t.player_shape.inner_space_h
+    }),
+];
+
+const CAMERA_PARAMETERS: &[Parameter<CameraTuning>] = &[
+    ("camera speed", 0.0..30.0, |t| &mut t.speed),
+    ("camera look_ahead", 0.0..2.0, |t| &mut t.look_ahead),
+    ("camera max_look_ahead", 0.0..1000.0, |t| {
+        &mut t.max_look_ahead
+    }),
+    ("camera dead_zone", 0.0..300.0, |t| &mut t.dead_zone),
+    ("camera min_zoom", 0.1..1.0, |t| &mut t.min_zoom),
+    ("camera zoom_rate", 0.0..10.0, |t| &mut t.zoom_rate),
+    ("camera shake_max_offset", 0.0..100.0, |t| {
+        &mut t.shake_max_offset
+    }),
+    ("camera shake_decay", 0.0..10.0, |t| &mut t.shake_decay),
+    ("camera shake_impact_speed", 1.0..3000.0, |t| {
+        &mut t.shake_impact_speed
+    }),
+    ("camera dash_shake", 0.0..1.0, |t| &mut t.dash_shake),
+];
+
+/// Window with a slider (and number box) for every tuning value.
+pub struct TuningPanel {
+    /// Recording/replaying only keeps the tuning from the start, so editing
+    /// is turned off then.
+    pub locked: bool,
+    export_path: PathBuf,
+    /// Result of the last export, or why the last edit was undone. Shown
+    /// under the buttons.
+    status: Option<String>,
+}
+
+impl TuningPanel {
+    pub fn new(locked: bool) -> Self {
+        Self {
+            locked,
+            export_path: TUNING_EXPORT_PATH.into(),
+            status: None,
+        }
+    }
+
+    /// Draws the panel and applies any edits to `tuning` and
+    /// `camera_tuning`, as long as `tuning` still validates. Returns false if
+    /// the window was closed.
+    pub fn draw(&mut self, tuning: &mut Tuning, camera_tuning: &mut CameraTuning) -> bool {
+        let position = vec2(20.0, 300.0);
+        let size = vec2(480.0, 420.0);
+
+        let before = tuning.clone();
+        let open = widgets::Window::new(hash!(), position, size)
+            .label("Tuning (5 to close)")
+            .close_button(true)
+            .ui(&mut root_ui(), |ui| {
+                if self.locked {
+                    ui.label(None, "Locked while recording or replaying");
+                    return;
+                }
+
+                if ui.button(None, "Reset to defaults") {
+                    *tuning = Tuning::default();
+                    *camera_tuning = CameraTuning::default();
+                }
+                ui.same_line(0.0);
+                if ui.button(None, "Export") {
+                    let text = format!("{}\n{}", tuning.to_toml(), camera_tuning.to_toml());
+                    self.status = Some(match std::fs::write(&self.export_path, text) {
+                        Ok(()) => format!("exported to {}", self.export_path.display()),
+                        Err(error) => format!("export failed: {}", error),
+                    });
+                }
+                if let Some(status) = &self.status {
+                    ui.label(None, status);
+                }
+                ui.separator();
+
+                for (label, range, value) in PARAMETERS {
+                    ui.slider(hash!(*label), label, range.clone(), value(tuning));
+                }
+                for (label, range, value) in CAMERA_PARAMETERS {
+                    ui.slider(hash!(*label), label, range.clone(), value(camera_tuning));
+                }
+
+                // These are f64 since they're compared against the sim clock.
+                // Only written back when moved, so they don't pick up f32
+                // rounding every frame.
+                let f64_parameters = [
+                    (
+                        "crazy_dash_length",
+                        0.01..2.0,
+                        &mut tuning.crazy_dash_length,
+                    ),
+                    (
+                        "dash timing start",
+                        0.0..0.5,
+                        &mut tuning.crazy_dash_input_timing.start,
+                    ),
+                    (
+                        "dash timing end",
+                        0.0..0.5,
+                        &mut tuning.crazy_dash_input_timing.end,
+                    ),
+                    (
+                        "perfect timing start",
+                        0.0..0.5,
+                        &mut tuning.crazy_dash_perfect_timing.start,
+                    ),
+                    (
+                        "perfect timing end",
+                        0.0..0.5,
+                        &mut tuning.crazy_dash_perfect_timing.end,
+                    ),
+                    (
+                        "crazy_dash_cooldown",
+                        0.01..2.0,
+                        &mut tuning.crazy_dash_cooldown,
+                    ),
+                    (
+                        "crazy_dash_chain_window",
+                        0.01..5.0,
+                        &mut tuning.crazy_dash_chain_window,
+                    ),
+                ];
+                for (label, range, value) in f64_parameters {
+                    let mut value_f32 = *value as f32;
+                    ui.slider(hash!(label), label, range, &mut value_f32);
+                    if value_f32 != *value as f32 {
+                        *value = value_f32 as f64;
+                    }
+                }
+            });
+
+        // The sim never sees values it can't handle; they just snap back.
+        if *tuning != before
+            && let Err(error) = tuning.validate()
+        {
+            self.status = Some(error.to_string());
+            *tuning = before;
+        }
+        open
+    }
+}